@@ -45,11 +45,11 @@ pub fn get_all_rust_files(
                 if file_name.starts_with('.') {
                     continue;
                 }
-                if EXCLUDED_DIRS.contains(&file_name) {
-                    if !include.iter().any(|inc| inc == file_name) {
-                        eprintln!("{} {:?}", "Excluded".yellow(), path);
-                        continue;
-                    }
+                if EXCLUDED_DIRS.contains(&file_name)
+                    && !include.iter().any(|inc| inc == file_name)
+                {
+                    eprintln!("{} {:?}", "Excluded".yellow(), path);
+                    continue;
                 }
                 dirs.push(path.to_path_buf());
                 continue;
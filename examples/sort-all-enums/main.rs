@@ -1,7 +1,6 @@
 use {
     clap::Parser,
     codesort::*,
-    lazy_regex::*,
     std::{
         fs,
         io,
@@ -13,15 +12,12 @@ use {
 /// Directories we don't want to touch
 static EXCLUDED_DIRS: &[&str] = &[".git", "target", "build"];
 
-/// Keywords which, if found in the annotations before an enum, will
-/// prevent the enum variants from being sorted
-static EXCLUDING_KEYWORDS: &[&str] = &["repr", "serde", "PartialOrd", "Ord"];
-
 /// Sort all enums of all rust files found in the given directory
 ///
 /// Are excluded
 /// - files in .git, target and build directories
 /// - files which don't appear correct enough
+/// - enums with attributes containing one of the excluding keywords
 #[derive(Debug, Parser)]
 #[command(about, version)]
 pub struct Args {
@@ -33,6 +29,10 @@ pub struct Args {
     #[clap(long, default_value = "")]
     pub exclude: Vec<String>,
 
+    /// keywords which, found in the attributes of an enum, prevent its sort
+    #[clap(long, value_delimiter = ',')]
+    pub exclude_keywords: Option<Vec<String>>,
+
     /// Path to the file(s)
     pub path: PathBuf,
 }
@@ -64,11 +64,11 @@ pub fn get_all_rust_files(
                     eprintln!("{} {:?}", "Excluded".yellow(), path);
                     continue;
                 }
-                if EXCLUDED_DIRS.contains(&file_name) {
-                    if !include.iter().any(|inc| inc == file_name) {
-                        eprintln!("{} {:?}", "Excluded".yellow(), path);
-                        continue;
-                    }
+                if EXCLUDED_DIRS.contains(&file_name)
+                    && !include.iter().any(|inc| inc == file_name)
+                {
+                    eprintln!("{} {:?}", "Excluded".yellow(), path);
+                    continue;
                 }
                 dirs.push(path.to_path_buf());
                 continue;
@@ -87,12 +87,15 @@ fn main() -> CsResult<()> {
     let start = std::time::Instant::now();
     let args = Args::parse();
     let files = get_all_rust_files(args.path, &args.include, &args.exclude)?;
+    let mut selection = ItemSelection::new(vec![ItemKind::Enum]);
+    if let Some(keywords) = args.exclude_keywords {
+        selection.excluding_keywords = keywords;
+    }
     eprintln!("Found {} rust files", files.len());
     let mut sorted_enum_count = 0;
     let mut ok_files_count = 0;
     let mut invalid_files_count = 0;
     let mut incomplete_files_count = 0;
-    let mut excluded_enums_count = 0;
     let mut modified_files_count = 0;
     let mut empty_files_count = 0;
     for file in &files {
@@ -118,50 +121,18 @@ fn main() -> CsResult<()> {
             incomplete_files_count += 1;
             continue;
         }
-        let mut modified = false;
-        let mut line_idx = 0;
-        ok_files_count += 1;
-        while line_idx + 2 < loc_list.len() {
-            let loc = &loc_list.locs[line_idx];
-            if !loc.starts_normal {
-                line_idx += 1;
-                continue;
-            }
-            let content = &loc.content;
-            let Some((_, name)) =
-                regex_captures!(r"^[\s\w()]*\benum\s+([^({]+)\s+\{\s**$", content)
-            else {
-                line_idx += 1;
-                continue;
-            };
-
-            // We look whether the annotations before the enum contain any one
-            // of the excluding keywords
-            let whole_enum_range = loc_list
-                .block_range_of_line_number(LineNumber::from_index(line_idx))
-                .unwrap();
-            let whole_enum_range = loc_list.trimmed_range(whole_enum_range);
-            let excluding_keyword = EXCLUDING_KEYWORDS.iter().find(|&keyword| {
-                loc_list.locs[whole_enum_range.start.to_index()..line_idx]
-                    .iter()
-                    .any(|loc| loc.sort_key.contains(keyword))
-            });
-            if let Some(excluding_keyword) = excluding_keyword {
-                eprintln!("skipping enum {} ({})", name, excluding_keyword.yellow());
-                excluded_enums_count += 1;
-                line_idx = whole_enum_range.end.to_index() + 1;
-                continue;
-            }
-            loc_list.print_range_debug(
-                &format!(" sorting enum {} ", name.blue()),
-                whole_enum_range,
-            );
-            let range = loc_list.range_around_line_index(line_idx + 1).unwrap();
-            loc_list.sort_range(range).unwrap();
-            line_idx = range.end.to_index() + 2;
-            sorted_enum_count += 1;
-            modified = true;
+        let ranges = loc_list.item_ranges(Analyzer::Rust, &selection);
+        for &range in &ranges {
+            loc_list.print_range_debug(" sorting enum ", range);
+        }
+        if let Err(e) = loc_list.sort_ranges(&ranges) {
+            eprintln!("{} in {}: {:?}", "ERROR".red(), file.display(), e);
+            invalid_files_count += 1;
+            continue;
         }
+        ok_files_count += 1;
+        sorted_enum_count += ranges.len();
+        let modified = !ranges.is_empty();
         if modified {
             loc_list.write_file(file)?;
             eprintln!("wrote {}", file.display());
@@ -189,12 +160,6 @@ fn main() -> CsResult<()> {
             problems.join(", ")
         );
     }
-    if excluded_enums_count > 0 {
-        eprintln!(
-            "I excluded {} enums whose annotation contained excluding keywords",
-            excluded_enums_count
-        );
-    }
     eprintln!(
         "I sorted {} enums in {} files",
         sorted_enum_count, modified_files_count
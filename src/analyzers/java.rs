@@ -9,9 +9,11 @@ enum State {
     StarComment,
 }
 
-pub fn read<R: std::io::BufRead>(mut reader: R) -> CsResult<LocList> {
+pub fn read<R: std::io::BufRead>(
+    mut reader: R,
+    braces: &mut BraceStack,
+) -> CsResult<LocList> {
     let mut locs = Vec::new();
-    let mut braces = BraceStack::default();
     let mut last_is_antislash = false;
     let mut last_is_quote = false;
     let mut state = State::Normal;
@@ -33,8 +35,7 @@ pub fn read<R: std::io::BufRead>(mut reader: R) -> CsResult<LocList> {
         let mut sort_key = String::new();
         let wishes = Vec::new(); // not used in java
         let gifts = Vec::new(); // not used in java
-        loop {
-            let Some((i, c)) = chars.next() else { break };
+        for (i, c) in chars.by_ref() {
             match state {
                 State::Normal => {
                     match c {
@@ -99,7 +100,8 @@ pub fn read<R: std::io::BufRead>(mut reader: R) -> CsResult<LocList> {
             wishes,
             gifts,
             starts_normal,
+            pin: Pin::Unpinned,
         });
     }
-    Ok(LocList { locs })
+    Ok(LocList { locs, lang: None })
 }
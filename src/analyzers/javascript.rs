@@ -10,9 +10,25 @@ enum State {
     StarComment,
 }
 
-pub fn read<R: std::io::BufRead>(mut reader: R) -> CsResult<LocList> {
+/// Recognize the item from the tokens of its header, which must end
+/// with the brace opening its list
+pub fn item_kind(tokens: &[String]) -> Option<ItemKind> {
+    let (last, tokens) = tokens.split_last()?;
+    if last != "{" {
+        return None;
+    }
+    // a brace opens an object literal where a value is expected
+    match tokens.last().map(String::as_str) {
+        Some("=" | ":" | "(" | "," | "[" | "return") => Some(ItemKind::Object),
+        _ => None,
+    }
+}
+
+pub fn read<R: std::io::BufRead>(
+    mut reader: R,
+    braces: &mut BraceStack,
+) -> CsResult<LocList> {
     let mut locs = Vec::new();
-    let mut braces = BraceStack::default();
     let mut last_is_antislash = false;
     let mut state = State::Normal;
     loop {
@@ -33,8 +49,7 @@ pub fn read<R: std::io::BufRead>(mut reader: R) -> CsResult<LocList> {
         let mut sort_key = String::new();
         let wishes = Vec::new(); // not used in java
         let gifts = Vec::new(); // not used in java
-        loop {
-            let Some((i, c)) = chars.next() else { break };
+        for (i, c) in chars.by_ref() {
             match state {
                 State::Normal => {
                     match c {
@@ -106,10 +121,140 @@ pub fn read<R: std::io::BufRead>(mut reader: R) -> CsResult<LocList> {
             wishes,
             gifts,
             starts_normal,
+            pin: Pin::Unpinned,
         };
         locs.push(loc);
     }
-    Ok(LocList { locs })
+    Ok(LocList { locs, lang: None })
+}
+
+/// Operators made of several chars, longest first
+const OPERATORS: &[&str] = &[
+    ">>>=", "===", "!==", "**=", "...", "<<=", ">>=", ">>>", "&&=", "||=", "??=", "=>",
+    "==", "!=", "<=", ">=", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "&&", "||",
+    "??", "?.", "++", "--", "**", "<<", ">>",
+];
+
+/// Split JavaScript code into tokens, comments included, whitespaces
+/// excluded.
+///
+/// This is a rough tokenizer: literals (template literals included) and
+/// comments are kept whole, identifiers, numbers, and operators too, and
+/// every other char is a token.
+pub fn tokenize(code: &str) -> Vec<String> {
+    let chars: Vec<char> = code.chars().collect();
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/'))
+            {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '`' {
+            i = template_end(&chars, i);
+        } else if c == '"' || c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i += 1;
+        } else if is_word_char(c) {
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+        } else {
+            i += OPERATORS
+                .iter()
+                .find(|op| {
+                    op.chars()
+                        .enumerate()
+                        .all(|(j, o)| chars.get(i + j) == Some(&o))
+                })
+                .map_or(1, |op| op.len());
+        }
+        let end = i.min(chars.len());
+        tokens.push(
+            chars[start..end]
+                .iter()
+                .collect::<String>()
+                .trim_end()
+                .to_string(),
+        );
+    }
+    tokens
+}
+
+/// Return the index following the end of the template literal starting
+/// at `start`, the expressions of the literal being skipped
+fn template_end(
+    chars: &[char],
+    start: usize,
+) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() && chars[i] != '`' {
+        if chars[i] == '\\' {
+            i += 1;
+        } else if chars[i] == '$' && chars.get(i + 1) == Some(&'{') {
+            i += 2;
+            let mut depth = 1;
+            while i < chars.len() {
+                match chars[i] {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    '`' => i = template_end(chars, i) - 1,
+                    _ => {}
+                }
+                if depth == 0 {
+                    break;
+                }
+                i += 1;
+            }
+        }
+        i += 1;
+    }
+    i + 1
+}
+
+/// Return the expressions of a template literal, eg `a.b` and `c` for
+/// `` `x${a.b}y${c}` ``
+pub fn template_expressions(literal: &str) -> Vec<&str> {
+    let mut expressions = Vec::new();
+    let mut rest = literal;
+    while let Some(start) = rest.find("${") {
+        rest = &rest[start + 2..];
+        let mut depth = 1;
+        let end = rest
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        expressions.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    expressions
 }
 
 #[test]
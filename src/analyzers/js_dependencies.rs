@@ -0,0 +1,447 @@
+//! A rough analysis of the dependencies between JavaScript statements, to
+//! know which ones must keep their relative order.
+//!
+//! Only the code running when the statement is executed is considered:
+//! the body of a function runs when the function is called, so a function
+//! assignment doesn't depend on the definitions its body uses.
+//! Any call may read or change anything, so statements calling functions
+//! keep their order relative to the statements defining something.
+
+const KEYWORDS: &[&str] = &[
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "let",
+    "new",
+    "null",
+    "of",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+const ASSIGNMENTS: &[&str] = &[
+    "=", "+=", "-=", "*=", "/=", "%=", "**=", "<<=", ">>=", ">>>=", "&=", "|=", "^=",
+    "&&=", "||=", "??=",
+];
+
+/// What a statement defines and reads when it's executed
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StatementDeps {
+    /// The names, or dotted paths like `notif.init`, declared or assigned
+    pub defined: Vec<String>,
+    /// The names, or dotted paths, read
+    pub read: Vec<String>,
+    /// Whether the statement calls a function
+    pub calls: bool,
+}
+
+impl StatementDeps {
+    /// Analyze a statement, given its tokens, comments excluded
+    pub fn from_tokens(tokens: &[String]) -> Self {
+        let mut deps = Self::default();
+        // tokens not executed with the statement: functions and classes
+        let mut lazy = vec![false; tokens.len()];
+        // tokens naming what's defined, which aren't read
+        let mut targets = vec![false; tokens.len()];
+        if let Some(name) = method_name(tokens) {
+            let open = closing(tokens, name + 1) + 1;
+            lazy[..=closing(tokens, open)].fill(true);
+        }
+        for i in 0..tokens.len() {
+            match tokens[i].as_str() {
+                "function" | "class" => {
+                    let mut name = i + 1;
+                    if tokens.get(name).map_or(false, |t| t == "*") {
+                        name += 1;
+                    }
+                    if tokens.get(name).map_or(false, |t| is_identifier(t)) {
+                        deps.defined.push(tokens[name].clone());
+                        targets[name] = true;
+                    }
+                    let Some(open) = (i..tokens.len()).find(|&j| tokens[j] == "{") else {
+                        continue;
+                    };
+                    let start = if tokens[i] == "class" { open } else { i };
+                    let end = closing(tokens, open);
+                    lazy[start..=end].fill(true);
+                }
+                "=>" => {
+                    let start = match i.checked_sub(1) {
+                        Some(j) if tokens[j] == ")" => opening(tokens, j),
+                        Some(j) => j,
+                        None => i,
+                    };
+                    let end = match tokens.get(i + 1).map(String::as_str) {
+                        Some("{") => closing(tokens, i + 1),
+                        _ => expression_end(tokens, i + 1),
+                    };
+                    lazy[start..=end].fill(true);
+                }
+                _ => {}
+            }
+        }
+        for i in 0..tokens.len() {
+            if lazy[i] {
+                continue;
+            }
+            let token = tokens[i].as_str();
+            if matches!(token, "var" | "let" | "const") {
+                deps.declare(tokens, i + 1, &mut targets);
+            } else if ASSIGNMENTS.contains(&token) || token == "++" || token == "--" {
+                // the assigned path is before the operator, except for `++x`
+                let path_end = if i > 0 && is_identifier(&tokens[i - 1]) {
+                    Some(i - 1)
+                } else if token == "++" || token == "--" {
+                    path_len(tokens, i + 1).map(|len| i + len)
+                } else {
+                    None
+                };
+                let Some(path_end) = path_end else {
+                    continue;
+                };
+                let mut path_start = path_end;
+                while path_start >= 2
+                    && tokens[path_start - 1] == "."
+                    && is_identifier(&tokens[path_start - 2])
+                {
+                    path_start -= 2;
+                }
+                if targets[path_start] {
+                    continue; // already declared
+                }
+                let path = tokens[path_start..=path_end].concat();
+                if token != "=" {
+                    deps.read.push(path.clone());
+                }
+                deps.defined.push(path);
+                targets[path_start..=path_end].fill(true);
+            }
+        }
+        let mut i = 0;
+        while i < tokens.len() {
+            if lazy[i] || targets[i] {
+                i += 1;
+                continue;
+            }
+            let token = tokens[i].as_str();
+            let after_dot = i > 0 && (tokens[i - 1] == "." || tokens[i - 1] == "?.");
+            let calls_expression =
+                token == "(" && i > 0 && (tokens[i - 1] == ")" || tokens[i - 1] == "]");
+            if token == "new" || calls_expression {
+                deps.calls = true;
+            } else if is_identifier(token) && !after_dot {
+                let is_key = tokens.get(i + 1).map_or(false, |t| t == ":")
+                    && (i == 0 || tokens[i - 1] == "{" || tokens[i - 1] == ",");
+                let len = path_len(tokens, i).unwrap_or(1);
+                if !is_key {
+                    deps.read.push(tokens[i..i + len].concat());
+                    if tokens.get(i + len).map_or(false, |t| t == "(" || t == "`") {
+                        deps.calls = true;
+                    }
+                }
+                i += len;
+                continue;
+            }
+            i += 1;
+        }
+        deps.defined.dedup();
+        deps.read.dedup();
+        deps
+    }
+    /// Read the declarators of a `var`, `let`, or `const` declaration
+    fn declare(
+        &mut self,
+        tokens: &[String],
+        mut i: usize,
+        targets: &mut [bool],
+    ) {
+        while i < tokens.len() {
+            match tokens[i].as_str() {
+                "{" | "[" => {
+                    // a destructuring pattern
+                    let end = closing(tokens, i);
+                    for j in i + 1..end {
+                        let is_key = tokens.get(j + 1).map_or(false, |t| t == ":");
+                        if is_identifier(&tokens[j]) && !is_key {
+                            self.defined.push(tokens[j].clone());
+                            targets[j] = true;
+                        }
+                    }
+                    i = end + 1;
+                }
+                token if is_identifier(token) => {
+                    self.defined.push(token.to_string());
+                    targets[i] = true;
+                    i += 1;
+                }
+                _ => return,
+            }
+            // skip the initializer
+            i = expression_end(tokens, i) + 1;
+            if tokens.get(i).map_or(true, |t| t != ",") {
+                return;
+            }
+            i += 1;
+        }
+    }
+    /// Tell whether executing both statements in a different order could
+    /// change the behavior of the program
+    pub fn conflicts(
+        &self,
+        other: &Self,
+    ) -> bool {
+        let defines = |a: &Self, b: &Self| {
+            a.defined.iter().any(|d| {
+                b.defined.iter().any(|e| related(d, e))
+                    || b.read.iter().any(|r| related(d, r))
+            })
+        };
+        let calls = |a: &Self, b: &Self| a.calls && (b.calls || !b.defined.is_empty());
+        defines(self, other)
+            || defines(other, self)
+            || calls(self, other)
+            || calls(other, self)
+    }
+}
+
+/// Tell whether a path is the same as another one, or a part of it
+fn related(
+    a: &str,
+    b: &str,
+) -> bool {
+    let is_prefix = |a: &str, b: &str| {
+        b.strip_prefix(a)
+            .map_or(false, |rest| rest.is_empty() || rest.starts_with('.'))
+    };
+    is_prefix(a, b) || is_prefix(b, a)
+}
+
+fn is_identifier(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .map_or(false, |c| c.is_alphabetic() || c == '_' || c == '$')
+        && !KEYWORDS.contains(&token)
+}
+
+/// Return the index of the name of the method defined by the statement,
+/// like `static async load(url) { ... }` in a class body
+fn method_name(tokens: &[String]) -> Option<usize> {
+    let mut i = 0;
+    while tokens.get(i + 1).map_or(false, |t| t != "(")
+        && matches!(tokens[i].as_str(), "async" | "get" | "set" | "static" | "*")
+    {
+        i += 1;
+    }
+    if !is_identifier(tokens.get(i)?) || tokens.get(i + 1)? != "(" {
+        return None;
+    }
+    let close = closing(tokens, i + 1);
+    (tokens.get(close + 1)? == "{").then_some(i)
+}
+
+/// Return the number of tokens of the dotted path starting at `start`
+fn path_len(
+    tokens: &[String],
+    start: usize,
+) -> Option<usize> {
+    if !tokens.get(start).map_or(false, |t| is_identifier(t)) {
+        return None;
+    }
+    let mut end = start;
+    while tokens.get(end + 1).map_or(false, |t| t == ".")
+        && tokens.get(end + 2).map_or(false, |t| is_identifier(t))
+    {
+        end += 2;
+    }
+    Some(end + 1 - start)
+}
+
+/// Return the index of the token closing the bracket opened at `open`,
+/// or the last index if it's not closed
+fn closing(
+    tokens: &[String],
+    open: usize,
+) -> usize {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token.as_str() {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len().saturating_sub(1)
+}
+
+/// Return the index of the token opening the bracket closed at `close`
+fn opening(
+    tokens: &[String],
+    close: usize,
+) -> usize {
+    let mut depth = 0;
+    for i in (0..=close).rev() {
+        match tokens[i].as_str() {
+            ")" | "]" | "}" => depth += 1,
+            "(" | "[" | "{" => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    0
+}
+
+/// Return the index of the last token of the expression starting at
+/// `start`, which ends before a `,` or `;`, or before the bracket closing
+/// the one containing it
+fn expression_end(
+    tokens: &[String],
+    start: usize,
+) -> usize {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        match token.as_str() {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" if depth == 0 => return i.saturating_sub(1).max(start),
+            ")" | "]" | "}" => depth -= 1,
+            "," | ";" if depth == 0 => return i.saturating_sub(1).max(start),
+            _ => {}
+        }
+    }
+    tokens.len().saturating_sub(1).max(start)
+}
+
+/// Prepare the tokens of a statement for its analysis: comments are
+/// removed, and template literals are replaced with the tokens of their
+/// expressions
+pub fn expression_tokens(tokens: &[String]) -> Vec<String> {
+    let mut expression_tokens = Vec::new();
+    for token in tokens {
+        if token.starts_with("//") || token.starts_with("/*") {
+            continue;
+        }
+        if token.starts_with('`') {
+            expression_tokens.push("`".to_string());
+            for expression in super::javascript::template_expressions(token) {
+                let tokens = super::javascript::tokenize(expression);
+                expression_tokens.extend(self::expression_tokens(&tokens));
+            }
+            continue;
+        }
+        expression_tokens.push(token.clone());
+    }
+    expression_tokens
+}
+
+/// Return the pairs of statements which must keep their relative order,
+/// given the tokens of the blocks of a list
+pub fn order_constraints(blocks: &[Vec<String>]) -> Vec<(usize, usize)> {
+    let deps: Vec<StatementDeps> = blocks
+        .iter()
+        .map(|tokens| StatementDeps::from_tokens(&expression_tokens(tokens)))
+        .collect();
+    let mut constraints = Vec::new();
+    for (j, b) in deps.iter().enumerate() {
+        for (i, a) in deps[..j].iter().enumerate() {
+            if a.conflicts(b) {
+                constraints.push((i, j));
+            }
+        }
+    }
+    constraints
+}
+
+#[test]
+fn test_statement_deps() {
+    let deps = |code: &str| {
+        let tokens = super::javascript::tokenize(code);
+        StatementDeps::from_tokens(&expression_tokens(&tokens))
+    };
+    let strings =
+        |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    let d = deps("var a = b + 1, c = [d.e], f;");
+    assert_eq!(d.defined, strings(&["a", "c", "f"]));
+    assert_eq!(d.read, strings(&["b", "d.e"]));
+    assert!(!d.calls);
+
+    let d = deps("notif.init = function(x){ return a.b(x); };");
+    assert_eq!(d.defined, strings(&["notif.init"]));
+    assert!(d.read.is_empty());
+    assert!(!d.calls);
+
+    let d = deps("const { x, y: z } = load(config);");
+    assert_eq!(d.defined, strings(&["x", "z"]));
+    assert_eq!(d.read, strings(&["load", "config"]));
+    assert!(d.calls);
+
+    let d = deps("count += items.filter(i => i.ok).length;");
+    assert_eq!(d.defined, strings(&["count"]));
+    assert_eq!(d.read, strings(&["count", "items.filter"]));
+
+    let d = deps("var label = `${prefix}: ${count + 1}`; // counted");
+    assert_eq!(d.defined, strings(&["label"]));
+    assert_eq!(d.read, strings(&["prefix", "count"]));
+
+    let d = deps("static async load(url) { return fetch(url); }");
+    assert!(d.defined.is_empty());
+    assert!(d.read.is_empty());
+    assert!(!d.calls);
+
+    let d = deps("if (ready) { start(); }");
+    assert_eq!(d.read, strings(&["ready", "start"]));
+    assert!(d.calls);
+
+    let d = deps("function helper() { return total; }");
+    assert_eq!(d.defined, strings(&["helper"]));
+    assert!(d.read.is_empty());
+
+    assert!(deps("var a = 1;").conflicts(&deps("var b = a * 2;")));
+    assert!(deps("var a = 1;").conflicts(&deps("var a = 2;")));
+    assert!(deps("notif = {};").conflicts(&deps("notif.a = 3;")));
+    assert!(!deps("notif.a = 1;").conflicts(&deps("notif.b = notif.c;")));
+    assert!(!deps("notif.a = function(){ return b; };").conflicts(&deps("var b = 2;")));
+    assert!(deps("init();").conflicts(&deps("var b = 2;")));
+    assert!(!deps("var a = 1;").conflicts(&deps("var b = 2;")));
+}
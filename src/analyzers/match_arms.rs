@@ -0,0 +1,422 @@
+//! A rough analysis of the patterns of Rust match arms, to know which
+//! arms may match the same values, and thus must keep their order.
+//!
+//! When a pattern isn't understood, it's assumed to match anything.
+
+/// A simplified Rust pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pat {
+    /// Matches anything, or at least something we don't understand:
+    /// `_`, bindings, ranges, slices, etc.
+    Wild,
+    /// A literal, like `0` or `"red"`
+    Lit(String),
+    /// A path (unit variant, constant) or a tuple-like pattern (the name
+    /// of a tuple being empty)
+    Ctor {
+        name: String,
+        args: Vec<Pat>,
+        rest: bool,
+    },
+    /// A struct-like pattern
+    Struct {
+        name: String,
+        fields: Vec<(String, Pat)>,
+    },
+}
+
+/// The pattern of a match arm, as a list of alternatives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArmPattern {
+    alternatives: Vec<Pat>,
+}
+
+impl ArmPattern {
+    /// Read the pattern of a match arm, given the tokens of the arm.
+    ///
+    /// Return `None` when the tokens aren't the ones of a match arm.
+    /// The guard, if any, is ignored: a guarded arm matches less than its
+    /// pattern, but may still match the same values than another arm.
+    pub fn from_tokens(tokens: &[String]) -> Option<Self> {
+        let arrow = arrow_position(tokens)?;
+        let mut depth = 0;
+        let mut alternatives = Vec::new();
+        let mut start = 0;
+        for (i, token) in tokens[..arrow].iter().enumerate() {
+            match token.as_str() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                "if" if depth == 0 => break,
+                "|" if depth == 0 => {
+                    // there may be a leading `|`
+                    if i > start {
+                        alternatives.push(parse(&tokens[start..i]));
+                    }
+                    start = i + 1;
+                    continue;
+                }
+                _ => {}
+            }
+            if i + 1 == arrow || tokens[i + 1] == "if" && depth == 0 {
+                alternatives.push(parse(&tokens[start..=i]));
+            }
+        }
+        if alternatives.is_empty() {
+            return None;
+        }
+        Some(Self { alternatives })
+    }
+    /// Tell whether some value may be matched by both patterns
+    pub fn overlaps(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.alternatives
+            .iter()
+            .any(|a| other.alternatives.iter().any(|b| a.overlaps(b)))
+    }
+}
+
+/// Find the `=>` ending the pattern (and guard) of an arm
+fn arrow_position(tokens: &[String]) -> Option<usize> {
+    let mut depth = 0;
+    for i in 0..tokens.len().saturating_sub(1) {
+        match tokens[i].as_str() {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => depth -= 1,
+            "=" if depth == 0 && tokens[i + 1] == ">" => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parse a pattern, assumed to be made of all the tokens
+fn parse(tokens: &[String]) -> Pat {
+    let mut parser = Parser { tokens, pos: 0 };
+    match parser.pat() {
+        Some(pat) if parser.pos == tokens.len() => pat,
+        _ => Pat::Wild,
+    }
+}
+
+struct Parser<'t> {
+    tokens: &'t [String],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(
+        &self,
+        offset: usize,
+    ) -> Option<&str> {
+        self.tokens.get(self.pos + offset).map(String::as_str)
+    }
+    fn eat(
+        &mut self,
+        token: &str,
+    ) -> bool {
+        if self.peek(0) == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+    /// Eat a `..` or `..=`, return whether it was there
+    fn eat_range(&mut self) -> bool {
+        if self.peek(0) == Some(".") && self.peek(1) == Some(".") {
+            self.pos += 2;
+            self.eat("=");
+            true
+        } else {
+            false
+        }
+    }
+    fn pat(&mut self) -> Option<Pat> {
+        while self.eat("&") || self.eat("ref") || self.eat("mut") {}
+        let token = self.peek(0)?.to_string();
+        if token == "_" {
+            self.pos += 1;
+            return Some(Pat::Wild);
+        }
+        if token == "(" {
+            self.pos += 1;
+            let (args, rest) = self.args(")")?;
+            return Some(Pat::Ctor {
+                name: String::new(),
+                args,
+                rest,
+            });
+        }
+        if token == "[" {
+            self.pos += 1;
+            self.args("]")?;
+            return Some(Pat::Wild);
+        }
+        if token == "." {
+            // a range without start
+            self.eat_range();
+            self.pat()?;
+            return Some(Pat::Wild);
+        }
+        let negative = self.eat("-");
+        let token = self.peek(0)?.to_string();
+        if is_literal(&token) {
+            self.pos += 1;
+            if self.eat_range() {
+                // the end of a range is optional
+                if self.peek(0).map_or(false, |t| is_literal(t) || t == "-") {
+                    self.pat()?;
+                }
+                return Some(Pat::Wild);
+            }
+            let lit = if negative { format!("-{token}") } else { token };
+            return Some(Pat::Lit(lit));
+        }
+        if !is_identifier(&token) {
+            return None;
+        }
+        let name = self.path()?;
+        if self.eat("@") {
+            return self.pat();
+        }
+        if self.eat_range() {
+            if self
+                .peek(0)
+                .map_or(false, |t| t != "," && t != ")" && t != "]")
+            {
+                self.pat()?;
+            }
+            return Some(Pat::Wild);
+        }
+        if self.eat("(") {
+            let (args, rest) = self.args(")")?;
+            return Some(Pat::Ctor { name, args, rest });
+        }
+        if self.eat("{") {
+            let fields = self.fields()?;
+            return Some(Pat::Struct { name, fields });
+        }
+        if name == "true" || name == "false" {
+            return Some(Pat::Lit(name));
+        }
+        let is_binding = !name.contains("::")
+            && name
+                .chars()
+                .next()
+                .map_or(false, |c| c.is_lowercase() || c == '_');
+        if is_binding {
+            return Some(Pat::Wild);
+        }
+        Some(Pat::Ctor {
+            name,
+            args: Vec::new(),
+            rest: false,
+        })
+    }
+    /// Read a path like `Level::Warn`, the first token being an identifier
+    fn path(&mut self) -> Option<String> {
+        let mut path = self.peek(0)?.to_string();
+        self.pos += 1;
+        while self.peek(0) == Some(":") && self.peek(1) == Some(":") {
+            let segment = self.peek(2).filter(|t| is_identifier(t))?.to_string();
+            path.push_str("::");
+            path.push_str(&segment);
+            self.pos += 3;
+        }
+        Some(path)
+    }
+    /// Read comma separated patterns, up to the closing token, telling
+    /// whether there's a `..` rest
+    fn args(
+        &mut self,
+        closing: &str,
+    ) -> Option<(Vec<Pat>, bool)> {
+        let mut args = Vec::new();
+        let mut rest = false;
+        loop {
+            if self.eat(closing) {
+                return Some((args, rest));
+            }
+            if self.peek(0) == Some(".") && self.peek(1) == Some(".") {
+                self.pos += 2;
+                rest = true;
+            } else {
+                args.push(self.pat()?);
+            }
+            if !self.eat(",") && self.peek(0) != Some(closing) {
+                return None;
+            }
+        }
+    }
+    /// Read the fields of a struct pattern, up to the closing brace
+    fn fields(&mut self) -> Option<Vec<(String, Pat)>> {
+        let mut fields = Vec::new();
+        loop {
+            if self.eat("}") {
+                return Some(fields);
+            }
+            if self.peek(0) == Some(".") && self.peek(1) == Some(".") {
+                self.pos += 2;
+            } else {
+                while self.eat("ref") || self.eat("mut") {}
+                let name = self.peek(0).filter(|t| is_identifier(t))?.to_string();
+                self.pos += 1;
+                let pat = if self.eat(":") {
+                    self.pat()?
+                } else {
+                    Pat::Wild
+                };
+                fields.push((name, pat));
+            }
+            if !self.eat(",") && self.peek(0) != Some("}") {
+                return None;
+            }
+        }
+    }
+}
+
+impl Pat {
+    fn overlaps(
+        &self,
+        other: &Self,
+    ) -> bool {
+        match (self, other) {
+            (Self::Wild, _) | (_, Self::Wild) => true,
+            // a constant may have the value matched by any other pattern
+            (a, b) if a.may_be_constant() || b.may_be_constant() => true,
+            (Self::Lit(a), Self::Lit(b)) => a == b,
+            (
+                Self::Ctor {
+                    name: a,
+                    args: a_args,
+                    rest: a_rest,
+                },
+                Self::Ctor {
+                    name: b,
+                    args: b_args,
+                    rest: b_rest,
+                },
+            ) => {
+                a == b
+                    && (*a_rest
+                        || *b_rest
+                        || (a_args.len() == b_args.len()
+                            && a_args.iter().zip(b_args).all(|(a, b)| a.overlaps(b))))
+            }
+            (
+                Self::Struct {
+                    name: a,
+                    fields: a_fields,
+                },
+                Self::Struct {
+                    name: b,
+                    fields: b_fields,
+                },
+            ) => {
+                a == b
+                    && a_fields.iter().all(|(a_name, a)| {
+                        b_fields
+                            .iter()
+                            .filter(|(b_name, _)| b_name == a_name)
+                            .all(|(_, b)| a.overlaps(b))
+                    })
+            }
+            (Self::Ctor { name: a, .. }, Self::Struct { name: b, .. })
+            | (Self::Struct { name: a, .. }, Self::Ctor { name: b, .. }) => a == b,
+            _ => false,
+        }
+    }
+    /// Tell whether the pattern is a path which may name a constant, like
+    /// `MAX` or `u8::MAX`, rather than a unit variant
+    fn may_be_constant(&self) -> bool {
+        match self {
+            Self::Ctor { name, args, rest } => {
+                args.is_empty() && !rest && is_constant_name(name)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn is_identifier(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .map_or(false, |c| c.is_alphabetic() || c == '_')
+}
+
+fn is_literal(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .map_or(false, |c| c.is_ascii_digit() || c == '"' || c == '\'')
+        || token.starts_with("b'")
+        || token.starts_with("b\"")
+}
+
+/// Tell whether the name looks like the one of a constant, in
+/// SCREAMING_CASE (a single letter, like `C`, being rather a variant)
+fn is_constant_name(name: &str) -> bool {
+    let last = name.rsplit("::").next().unwrap_or(name);
+    last.len() > 1
+        && last.starts_with(|c: char| c.is_ascii_uppercase())
+        && last
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Return the pairs of arms which must keep their relative order, given
+/// the tokens of the blocks of a list, or nothing if they're not all
+/// match arms
+pub fn order_constraints(blocks: &[Vec<String>]) -> Vec<(usize, usize)> {
+    let patterns: Option<Vec<ArmPattern>> = blocks
+        .iter()
+        .map(|tokens| ArmPattern::from_tokens(tokens))
+        .collect();
+    let Some(patterns) = patterns else {
+        return Vec::new();
+    };
+    let mut constraints = Vec::new();
+    for (j, b) in patterns.iter().enumerate() {
+        for (i, a) in patterns[..j].iter().enumerate() {
+            if a.overlaps(b) {
+                constraints.push((i, j));
+            }
+        }
+    }
+    constraints
+}
+
+#[test]
+fn test_arm_overlaps() {
+    let pattern = |code: &str| {
+        let tokens = super::rust::tokenize(&format!("{code} => 0,"));
+        ArmPattern::from_tokens(&tokens).unwrap()
+    };
+    let overlap = |a: &str, b: &str| pattern(a).overlaps(&pattern(b));
+    assert!(overlap("Some(0)", "Some(_)"));
+    assert!(overlap("Some(0)", "Some(n)"));
+    assert!(!overlap("Some(0)", "Some(1)"));
+    assert!(!overlap("Some(0)", "None"));
+    assert!(overlap("Some(n) if n > 3", "Some(5)"));
+    assert!(overlap("x if x > 3", "Level::Warn"));
+    assert!(overlap("1..=5", "3"));
+    assert!(!overlap("Level::Warn", "Level::Error"));
+    assert!(overlap("Level::Warn | Level::Error", "Level::Error"));
+    assert!(overlap("Point { x: 0, .. }", "Point { y: 0, .. }"));
+    assert!(!overlap("Point { x: 0, y }", "Point { x: 1, y: 2 }"));
+    assert!(overlap("(0, _)", "(_, 1)"));
+    assert!(!overlap("(0, _)", "(1, _)"));
+    assert!(overlap("MAX", "255"));
+    assert!(overlap("FOO", "BAR"));
+    assert!(overlap("u8::MAX", "Limits::LOW"));
+    assert!(overlap("DEFAULT_SHAPE", "Shape::Circle(_)"));
+    assert!(overlap("ORIGIN", "Point { x: 0, y: 0 }"));
+    assert!(!overlap("Level::Warn", "Level::Info"));
+    assert!(!overlap("Self::C", "Self::Zig"));
+    assert!(!overlap("\"red\"", "\"blue\""));
+    assert!(overlap("Shape::Circle(r) if r > 1.0", "Shape::Circle(..)"));
+    assert!(ArmPattern::from_tokens(&super::rust::tokenize("Alpha,")).is_none());
+}
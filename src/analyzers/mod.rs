@@ -5,6 +5,8 @@ use {
 
 pub mod java;
 pub mod javascript;
+pub mod js_dependencies;
+pub mod match_arms;
 pub mod rust;
 
 /// The language syntax to use for analyzing the code
@@ -49,13 +51,93 @@ pub enum Analyzer {
 
 impl Analyzer {
     pub fn read<R: std::io::BufRead>(
+        &self,
+        reader: R,
+    ) -> CsResult<LocList> {
+        self.read_with_braces(reader, &mut BraceStack::default())
+    }
+    /// Read the code, starting with the given brace stack, which is
+    /// left in its final state
+    pub fn read_with_braces<R: std::io::BufRead>(
         &self,
         mut reader: R,
+        braces: &mut BraceStack,
     ) -> CsResult<LocList> {
         match self {
-            Self::Java => java::read(&mut reader),
-            Self::Javascript => javascript::read(&mut reader),
-            Self::Rust => rust::read(&mut reader),
+            Self::Java => java::read(&mut reader, braces),
+            Self::Javascript => javascript::read(&mut reader, braces),
+            Self::Rust => rust::read(&mut reader, braces),
+        }
+    }
+    /// Split code into tokens, comments included, whitespaces excluded,
+    /// literals and comments being kept whole
+    pub fn tokenize(
+        &self,
+        code: &str,
+    ) -> Vec<String> {
+        match self {
+            // Java literals and comments are close enough to the JS ones
+            Self::Java | Self::Javascript => javascript::tokenize(code),
+            Self::Rust => rust::tokenize(code),
+        }
+    }
+    /// Recognize the item whose header, up to the brace opening its
+    /// list, is made of the given tokens (comments excluded)
+    pub fn item_kind(
+        &self,
+        header_tokens: &[String],
+    ) -> Option<ItemKind> {
+        match self {
+            Self::Java => None,
+            Self::Javascript => javascript::item_kind(header_tokens),
+            Self::Rust => rust::item_kind(header_tokens),
+        }
+    }
+    /// Return the pairs of blocks, given by their indices, whose relative
+    /// order must be kept when sorting, because changing it could change
+    /// the behavior of the program (eg overlapping match arms in Rust, or
+    /// JS statements using what other ones define)
+    pub fn order_constraints(
+        &self,
+        blocks: &[LocList],
+    ) -> Vec<(usize, usize)> {
+        match self {
+            Self::Java => Vec::new(),
+            Self::Javascript => {
+                let blocks: Vec<Vec<String>> = blocks
+                    .iter()
+                    .map(|block| self.tokenize(&block.to_string()))
+                    .collect();
+                js_dependencies::order_constraints(&blocks)
+            }
+            Self::Rust => {
+                let blocks: Vec<Vec<String>> =
+                    blocks.iter().map(LocList::code_tokens).collect();
+                match_arms::order_constraints(&blocks)
+            }
+        }
+    }
+    /// If sorting the list of the item could change the behavior of the
+    /// program, tell why. The blocks of the list are given as tokens.
+    pub fn order_dependency(
+        &self,
+        item: &Item,
+        blocks: &[Vec<String>],
+    ) -> Option<&'static str> {
+        match self {
+            Self::Java | Self::Javascript => None,
+            Self::Rust => rust::order_dependency(item, blocks),
+        }
+    }
+    /// If sorting the fields of a variant of the enum item could change
+    /// the behavior of the program, tell why
+    pub fn variant_fields_order_dependency(
+        &self,
+        item: &Item,
+    ) -> Option<&'static str> {
+        match self {
+            Self::Java | Self::Javascript => None,
+            Self::Rust => rust::variant_fields_order_dependency(item),
         }
     }
 }
@@ -12,10 +12,7 @@ enum State {
 }
 
 fn char_is_gift(c: char) -> bool {
-    match c {
-        '(' | '{' | ';' => true,
-        _ => false,
-    }
+    matches!(c, '(' | '{' | ';')
 }
 
 /// Return what the token calls for: we don't want to have blocks between
@@ -40,9 +37,11 @@ fn token_wishes(token: &str) -> Vec<CharSet> {
     }
 }
 
-pub fn read<R: std::io::BufRead>(mut reader: R) -> CsResult<LocList> {
+pub fn read<R: std::io::BufRead>(
+    mut reader: R,
+    braces: &mut BraceStack,
+) -> CsResult<LocList> {
     let mut locs = Vec::new();
-    let mut braces = BraceStack::default();
     let mut last_is_antislash = false;
     let mut state = State::Normal;
     let mut annotation_start_depth = None;
@@ -73,13 +72,13 @@ pub fn read<R: std::io::BufRead>(mut reader: R) -> CsResult<LocList> {
         let mut current_token = String::new();
         let mut wishes = Vec::new();
         let mut gifts = Vec::new();
-        if annotation_start_depth.is_none() && state == State::Normal {
-            if indented.starts_with("#[") {
-                annotation_start_depth = Some(braces.depth());
-            }
+        if annotation_start_depth.is_none()
+            && state == State::Normal
+            && indented.starts_with("#[")
+        {
+            annotation_start_depth = Some(braces.depth());
         }
-        loop {
-            let Some((i, c)) = chars.next() else { break };
+        for (i, c) in chars.by_ref() {
             let token = if c.is_ascii_alphabetic() || c == '_' {
                 // we're only interested in possible keywords
                 current_token.push(c);
@@ -234,10 +233,11 @@ pub fn read<R: std::io::BufRead>(mut reader: R) -> CsResult<LocList> {
                             state = State::Normal;
                         }
                     }
-                    '/' if !last_is_antislash => {
-                        if i + 1 < bytes.len() && bytes[i + 1] == b'*' {
-                            state = State::BlockComment(depth + 1);
-                        }
+                    '/' if !last_is_antislash
+                        && i + 1 < bytes.len()
+                        && bytes[i + 1] == b'*' =>
+                    {
+                        state = State::BlockComment(depth + 1);
                     }
                     _ => {}
                 },
@@ -245,6 +245,11 @@ pub fn read<R: std::io::BufRead>(mut reader: R) -> CsResult<LocList> {
             last_is_antislash = c == '\\' && !last_is_antislash;
         }
         let is_annotation = indented.starts_with("#[");
+        let pin = if starts_normal {
+            Pin::of_rust_line(indented)
+        } else {
+            Pin::Unpinned
+        };
         let last_significant_char = sort_key.chars().rev().find(|c| !c.is_whitespace());
         let can_complete = last_significant_char
             .map_or(false, |c| char_is_brace(c) || c == ',' || c == ';');
@@ -259,10 +264,259 @@ pub fn read<R: std::io::BufRead>(mut reader: R) -> CsResult<LocList> {
             wishes,
             gifts,
             starts_normal,
+            pin,
         });
         line_index += 1;
     }
-    Ok(LocList { locs })
+    Ok(LocList { locs, lang: None })
+}
+
+/// Recognize the item from the tokens of its header, which must end
+/// with the brace opening its list
+pub fn item_kind(tokens: &[String]) -> Option<ItemKind> {
+    let (last, tokens) = tokens.split_last()?;
+    if last != "{" {
+        return None;
+    }
+    // items may only be preceded by a visibility and qualifiers
+    let item_keyword = tokens.iter().find(|token| {
+        !matches!(
+            token.as_str(),
+            "pub"
+                | "("
+                | ")"
+                | "crate"
+                | "super"
+                | "self"
+                | "in"
+                | "unsafe"
+                | "default"
+                | "auto"
+                | "typedef"
+        )
+    });
+    match item_keyword.map(String::as_str) {
+        Some("enum") => return Some(ItemKind::Enum),
+        Some("impl") => return Some(ItemKind::Impl),
+        Some("struct") => return Some(ItemKind::Struct),
+        Some("trait") => return Some(ItemKind::Trait),
+        _ => {}
+    }
+    let mut depth = 0;
+    for token in tokens {
+        match token.as_str() {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => depth -= 1,
+            "match" if depth == 0 => return Some(ItemKind::Match),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// If sorting the list of the item could change the behavior of the
+/// program, tell why
+pub fn order_dependency(
+    item: &Item,
+    blocks: &[Vec<String>],
+) -> Option<&'static str> {
+    if !matches!(item.kind, ItemKind::Enum | ItemKind::Struct) {
+        return None;
+    }
+    let attributes = &item.attribute_tokens;
+    let has = |token: &str| attributes.iter().any(|t| t == token);
+    if has("PartialOrd") || has("Ord") {
+        return Some("derived PartialOrd and Ord compare in order of declaration");
+    }
+    if item.kind == ItemKind::Enum && has("serde") && has("untagged") {
+        return Some("untagged variants are deserialized in order of declaration");
+    }
+    // the arguments of the repr attributes
+    let mut reprs = Vec::new();
+    for (i, token) in attributes.iter().enumerate() {
+        if token == "repr" {
+            reprs.extend(
+                attributes[i + 1..]
+                    .iter()
+                    .skip_while(|t| *t == "(")
+                    .take_while(|t| *t != ")")
+                    .filter(|t| *t != ","),
+            );
+        }
+    }
+    if reprs.is_empty() {
+        return None;
+    }
+    match item.kind {
+        ItemKind::Struct if reprs.iter().any(|r| *r != "transparent") => {
+            Some("the memory layout of a repr struct follows field order")
+        }
+        ItemKind::Enum
+            if blocks
+                .iter()
+                .any(|tokens| !tokens.is_empty() && !tokens.iter().any(|t| t == "=")) =>
+        {
+            Some("implicit discriminants follow variant order")
+        }
+        _ => None,
+    }
+}
+
+/// Tell why sorting the fields of a variant of the enum item could change
+/// the behavior of the program, if it could
+pub fn variant_fields_order_dependency(item: &Item) -> Option<&'static str> {
+    if item.kind != ItemKind::Enum {
+        return None;
+    }
+    let attributes = &item.attribute_tokens;
+    let has = |token: &str| attributes.iter().any(|t| t == token);
+    if has("PartialOrd") || has("Ord") {
+        return Some(
+            "derived PartialOrd and Ord compare variant fields in order of declaration",
+        );
+    }
+    None
+}
+
+/// Split Rust code into tokens, comments included, whitespaces excluded.
+///
+/// This is a rough tokenizer, only meant to compare code before and after
+/// sorting: literals and comments are kept whole, identifiers and numbers
+/// too, and every other char is a token.
+pub fn tokenize(code: &str) -> Vec<String> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            let mut depth = 0;
+            while i < chars.len() {
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                    depth += 1;
+                    i += 2;
+                } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        } else if c == 'r' && matches!(chars.get(i + 1), Some('"') | Some('#')) {
+            // raw string, or a raw identifier
+            i += 1;
+            let mut sharp_count = 0;
+            while chars.get(i) == Some(&'#') {
+                sharp_count += 1;
+                i += 1;
+            }
+            if chars.get(i) == Some(&'"') {
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == '"'
+                        && (1..=sharp_count).all(|j| chars.get(i + j) == Some(&'#'))
+                    {
+                        i += 1 + sharp_count;
+                        break;
+                    }
+                    i += 1;
+                }
+            } else {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+            }
+        } else if c == '"' {
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i += 1;
+        } else if c == '\'' {
+            // either a char literal or a lifetime
+            let is_char = match (chars.get(i + 1), chars.get(i + 2)) {
+                (Some('\\'), _) => true,
+                (Some(a), Some('\'')) => *a != '\'',
+                (Some(a), _) => !(a.is_alphanumeric() || *a == '_'),
+                _ => false,
+            };
+            i += 1;
+            if is_char {
+                while i < chars.len() && chars[i] != '\'' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            } else {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+            }
+        } else if c.is_alphanumeric() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+        } else {
+            i += 1;
+        }
+        let end = i.min(chars.len());
+        tokens.push(
+            chars[start..end]
+                .iter()
+                .collect::<String>()
+                .trim_end()
+                .to_string(),
+        );
+    }
+    tokens
+}
+
+#[test]
+fn test_tokenize() {
+    let code = r##"
+        /// doc
+        Found { pos: usize }, // found
+        s = r#"a "b" c"#; 'a' 'l /* a /* nested */ comment */ '\''
+    "##;
+    assert_eq!(
+        tokenize(code),
+        vec![
+            "/// doc",
+            "Found",
+            "{",
+            "pos",
+            ":",
+            "usize",
+            "}",
+            ",",
+            "// found",
+            "s",
+            "=",
+            r##"r#"a "b" c"#"##,
+            ";",
+            "'a'",
+            "'l",
+            "/* a /* nested */ comment */",
+            "'\\''",
+        ]
+    );
 }
 
 #[test]
@@ -3,27 +3,51 @@ use crate::*;
 #[derive(Debug, Clone, Default)]
 pub struct BraceStack {
     pub braces: Vec<char>,
+    /// When lenient, a closing brace found while the stack is empty
+    /// isn't an error but is recorded in `unmatched`
+    pub lenient: bool,
+    /// The closing braces which couldn't be matched, in order of appearance
+    pub unmatched: Vec<char>,
 }
 
 impl BraceStack {
+    /// Make a stack which doesn't fail on closing braces found
+    /// while no brace is open (as happens when reading a selection
+    /// starting in the middle of a block)
+    pub fn lenient() -> Self {
+        Self {
+            lenient: true,
+            ..Self::default()
+        }
+    }
+    /// Make a stack already containing the opening braces matching the
+    /// given closing ones, so that they become the floor of the stack.
+    ///
+    /// The closing braces are given in order of appearance, so the
+    /// first one matches the top of the stack.
+    pub fn with_floor(closing_braces: &[char]) -> Self {
+        let braces = closing_braces
+            .iter()
+            .rev()
+            .filter_map(|&c| opening_brace(c))
+            .collect();
+        Self {
+            braces,
+            ..Self::default()
+        }
+    }
     pub fn push(
         &mut self,
         brace: char,
     ) -> CsResult<()> {
         match brace {
             '(' | '[' | '{' => self.braces.push(brace),
-            ')' => {
-                if self.braces.pop() != Some('(') {
-                    return Err(CsError::UnexpectedClosingBrace(brace));
+            ')' | ']' | '}' => {
+                if self.lenient && self.braces.is_empty() {
+                    self.unmatched.push(brace);
+                    return Ok(());
                 }
-            }
-            ']' => {
-                if self.braces.pop() != Some('[') {
-                    return Err(CsError::UnexpectedClosingBrace(brace));
-                }
-            }
-            '}' => {
-                if self.braces.pop() != Some('{') {
+                if self.braces.pop() != opening_brace(brace) {
                     return Err(CsError::UnexpectedClosingBrace(brace));
                 }
             }
@@ -43,8 +67,15 @@ impl BraceStack {
 }
 
 pub fn char_is_brace(c: char) -> bool {
-    match c {
-        '{' | '}' | '[' | ']' | '(' | ')' => true,
-        _ => false,
+    matches!(c, '{' | '}' | '[' | ']' | '(' | ')')
+}
+
+/// Return the opening brace matching a closing one
+pub fn opening_brace(closing: char) -> Option<char> {
+    match closing {
+        ')' => Some('('),
+        ']' => Some('['),
+        '}' => Some('{'),
+        _ => None,
     }
 }
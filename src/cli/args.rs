@@ -2,6 +2,7 @@ use {
     clap::{
         CommandFactory,
         Parser,
+        Subcommand,
         ValueEnum,
     },
     codesort::*,
@@ -33,13 +34,131 @@ pub struct Args {
     #[arg(long)]
     pub version: bool,
 
-    /// Sort the block around this 1-based line number
+    /// Sort the block around this 1-based line number (may be repeated)
     #[arg(long, value_name = "LINE")]
-    pub around: Option<LineNumber>,
+    pub around: Vec<LineNumber>,
 
-    /// Sort this `start:end` range of 1 based lines, both ends included
+    /// Sort this `start:end` range of 1 based lines, both ends included,
+    /// or this `line:col-line:col` selection, snapped to the blocks it
+    /// touches. A range on a single line sorts the items of the line.
+    /// May be repeated.
     #[arg(long)]
-    pub range: Option<LineNumberRange>,
+    pub range: Vec<TextRange>,
+
+    /// Sort the lists of all items of this kind in the file: enums,
+    /// structs, matches, impls, traits (Rust), or objects (JS).
+    /// May be repeated.
+    #[arg(long, value_name = "KIND")]
+    pub all: Vec<ItemKind>,
+
+    /// With --all, the comma separated keywords which, found in the
+    /// attributes of an item, prevent its sort (default: repr,serde,PartialOrd,Ord)
+    #[arg(long, value_name = "KEYWORDS", value_delimiter = ',')]
+    pub exclude_keywords: Option<Vec<String>>,
+
+    /// Also sort the lists nested in the sorted blocks, bottom-up
+    #[arg(long)]
+    pub recursive: bool,
+
+    /// With --recursive, the maximum number of levels to sort under
+    /// the sorted lists
+    #[arg(long, value_name = "DEPTH", requires = "recursive")]
+    pub max_depth: Option<usize>,
+
+    /// Names of blocks which must come first, in this order, the other
+    /// blocks following alphabetically (eg `--order Trace,Debug,Info`)
+    #[arg(long, value_delimiter = ',', value_name = "NAMES")]
+    pub order: Vec<String>,
+
+    /// Sort the blocks on the `key` capture of the regular expression in
+    /// their first matching line (eg `--key-regex '=> "(?P<key>\w+)"'`)
+    #[arg(long, value_name = "REGEX")]
+    pub key_regex: Option<KeyRegex>,
+
+    /// Sort Rust struct fields or parameters on their `type`, or on the
+    /// `alignment` of their type, descending (primitive types only)
+    #[arg(long, value_name = "KEY")]
+    pub by: Option<FieldKey>,
+
+    /// Sort the members of Rust impls and traits by category, then name,
+    /// the categories being optionally given in order (default:
+    /// `--members=type,const,constructor,pub,pub-crate,private`)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "CATEGORIES",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "type,const,constructor,pub,pub-crate,private"
+    )]
+    pub members: Option<Vec<MemberCategory>>,
+
+    /// Sort separately the groups of blocks separated by blank lines or
+    /// header comments (eg `// --- network errors ---`)
+    #[arg(long)]
+    pub groups: bool,
+
+    /// What to do with the blank lines between blocks: `keep` them as
+    /// they are, or `normalize` them (this may change the number of lines)
+    #[arg(long, default_value = "keep", value_name = "MODE")]
+    pub spacing: SpacingMode,
+
+    /// Where the comments directly above a block go: `with-block`,
+    /// `in-place`, or `at-end`
+    #[arg(long, default_value = "with-block", value_name = "POLICY")]
+    pub leading_comments: CommentPolicy,
+
+    /// Where the comments separated from the next block by a blank line go
+    #[arg(long, default_value = "with-block", value_name = "POLICY")]
+    pub detached_comments: CommentPolicy,
+
+    /// Where the comments after the last block go
+    #[arg(long, default_value = "at-end", value_name = "POLICY")]
+    pub trailing_comments: CommentPolicy,
+
+    /// Before sorting the variants of a C-like enum, write their implicit
+    /// discriminants, so that their values don't change
+    #[arg(long)]
+    pub explicit_discriminants: bool,
+
+    /// Warn about the blocks having the same code as a previous block
+    /// of their list
+    #[arg(long)]
+    pub warn_duplicates: bool,
+
+    /// Remove the blocks having the same code as a previous block of
+    /// their list, before sorting
+    #[arg(long)]
+    pub dedupe: bool,
+
+    /// Sort even lists whose order matters, like the variants of an
+    /// enum deriving `PartialOrd` (Rust), with a warning
+    #[arg(long)]
+    pub force: bool,
+
+    /// Accept input starting inside a block (eg a partial selection),
+    /// and sort it at the depth of its first line
+    #[arg(long)]
+    pub lenient: bool,
+
+    /// Verify, by comparing tokens, that the sorted blocks are exactly
+    /// the original ones (Rust, C and Zig only)
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Don't sort, but print the range to sort and the range and sort
+    /// key of each block
+    #[arg(long)]
+    pub blocks: bool,
+
+    /// Output format: the sorted code, or a JSON report of the sort
+    #[arg(long, default_value = "code")]
+    pub format: Format,
+
+    /// A cursor position, as `LINE` or `LINE:COL`, whose new position
+    /// after sort is printed on stderr (or given in the JSON report)
+    #[arg(long, value_name = "LINE[:COL]")]
+    pub cursor: Option<TextPosition>,
 
     /// Code language
     #[arg(short, long, default_value = "auto")]
@@ -60,6 +179,16 @@ pub struct Args {
 
     /// File to sort in place (shortcut for --src and --dst)
     pub file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// A codesort subcommand
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run a language server (LSP over stdio) offering sorts as code actions
+    Lsp,
 }
 
 impl Args {
@@ -74,6 +203,35 @@ impl Args {
         skin.italic = termimad::CompoundStyle::with_fg(ansi(79));
         printer.print_help();
     }
+    pub fn sort_options(&self) -> SortOptions {
+        SortOptions {
+            groups: self.groups,
+            spacing: self.spacing,
+            comments: CommentAttachment {
+                leading: self.leading_comments,
+                detached: self.detached_comments,
+                trailing: self.trailing_comments,
+            },
+            comparator: self.comparator(),
+            analyzer: Some(self.lang().analyzer()),
+        }
+    }
+    pub fn comparator(&self) -> BlockComparator {
+        let mut criteria = Vec::new();
+        if !self.order.is_empty() {
+            criteria.push(Criterion::Precedence(self.order.clone()));
+        }
+        if let Some(regex) = &self.key_regex {
+            criteria.push(Criterion::Key(regex.clone()));
+        }
+        if let Some(key) = self.by {
+            criteria.push(Criterion::Field(key));
+        }
+        if let Some(order) = &self.members {
+            criteria.push(Criterion::Members(order.clone()));
+        }
+        BlockComparator::with_criteria(criteria)
+    }
     pub fn lang(&self) -> Language {
         match self.lang {
             LangChoice::C => Language::C,
@@ -107,3 +265,14 @@ pub enum LangChoice {
     Rust,
     Zig,
 }
+
+/// The output format
+#[derive(Default, ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The sorted code
+    #[default]
+    Code,
+    /// A JSON report with the focused range, the block moves, the warnings,
+    /// and the text edits to apply
+    Json,
+}
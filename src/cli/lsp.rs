@@ -0,0 +1,361 @@
+//! A minimal language server, speaking LSP over stdio, and offering
+//! sorts as code actions.
+
+use {
+    codesort::*,
+    serde_json::{
+        Value,
+        json,
+    },
+    std::{
+        collections::HashMap,
+        io::{
+            self,
+            BufRead,
+            Write,
+        },
+        num::NonZeroUsize,
+        path::Path,
+    },
+};
+
+/// An open text document, as sent by the client
+struct Document {
+    text: String,
+    lang: Language,
+}
+
+#[derive(Default)]
+struct Server {
+    documents: HashMap<String, Document>,
+    exit: bool,
+}
+
+/// Run the language server until the client asks for exit or
+/// closes the stream
+pub fn run() -> CsResult<()> {
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+    let mut server = Server::default();
+    while let Some(message) = read_message(&mut reader)? {
+        for response in server.handle(&message) {
+            write_message(&mut writer, &response)?;
+        }
+        if server.exit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn read_message<R: BufRead>(reader: &mut R) -> CsResult<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(length) = line.strip_prefix("Content-Length:") {
+            content_length = length.trim().parse().ok();
+        }
+    }
+    let Some(content_length) = content_length else {
+        return Err(
+            io::Error::new(io::ErrorKind::InvalidData, "no Content-Length").into(),
+        );
+    };
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+fn write_message<W: Write>(
+    writer: &mut W,
+    message: &Value,
+) -> CsResult<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn response(
+    id: Value,
+    result: Value,
+) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(
+    id: Value,
+    code: i32,
+    message: &str,
+) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Convert a LSP language identifier, or the extension of the uri,
+/// into the language to use for analysis
+fn language(
+    language_id: &str,
+    uri: &str,
+) -> Language {
+    match language_id {
+        "c" | "cpp" => Language::C,
+        "java" => Language::Java,
+        "javascript" | "javascriptreact" | "typescript" | "typescriptreact" => {
+            Language::Javascript
+        }
+        "rust" => Language::Rust,
+        "zig" => Language::Zig,
+        _ => Language::detect(Path::new(uri)).unwrap_or_default(),
+    }
+}
+
+/// Convert a LSP range, with 0-based lines and characters, the end
+/// being excluded, into a text range
+fn text_range(range: &Value) -> TextRange {
+    let position = |position: &Value, offset: u64| TextPosition {
+        line: LineNumber::from_index(
+            position["line"].as_u64().unwrap_or_default() as LineIndex
+        ),
+        column: NonZeroUsize::new(
+            (position["character"].as_u64().unwrap_or_default() + offset) as usize,
+        ),
+    };
+    let start = position(&range["start"], 1);
+    let mut end = position(&range["end"], 0);
+    if end.column.is_none() && end.line > start.line {
+        // the selection ends at the start of a line, which isn't selected
+        end.line = LineNumber::from_index(end.line.to_index() - 1);
+    }
+    TextRange { start, end }
+}
+
+/// Convert line edits into LSP `TextEdit`s
+fn lsp_edits(edits: &[TextEdit]) -> Value {
+    edits
+        .iter()
+        .map(|edit| {
+            json!({
+                "range": {
+                    "start": { "line": edit.start.to_index(), "character": 0 },
+                    "end": { "line": edit.end.to_index() + 1, "character": 0 },
+                },
+                "newText": edit.replacement,
+            })
+        })
+        .collect()
+}
+
+/// Sort the given ranges, checking every sort, and refusing the lists
+/// whose order matters
+fn sort_ranges(
+    list: &LocList,
+    lang: Language,
+    ranges: &[LineNumberRange],
+) -> CsResult<LocList> {
+    let mut list = list.clone();
+    for range in LineNumberRange::sort_order(ranges)? {
+        list.check_order_independent(range, lang.analyzer())?;
+        list = list.focus(range)?.sort_checked(lang)?;
+    }
+    Ok(list)
+}
+
+impl Server {
+    /// Handle a message from the client, return the messages to send back
+    fn handle(
+        &mut self,
+        message: &Value,
+    ) -> Vec<Value> {
+        let Some(method) = message["method"].as_str() else {
+            return vec![]; // a response from the client
+        };
+        let id = message.get("id").cloned();
+        let params = &message["params"];
+        match (method, id) {
+            ("initialize", Some(id)) => {
+                let result = json!({
+                    "capabilities": {
+                        "textDocumentSync": 1, // full
+                        "codeActionProvider": true,
+                    },
+                    "serverInfo": {
+                        "name": "codesort",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                });
+                vec![response(id, result)]
+            }
+            ("shutdown", Some(id)) => vec![response(id, Value::Null)],
+            ("exit", _) => {
+                self.exit = true;
+                vec![]
+            }
+            ("textDocument/didOpen", None) => {
+                let doc = &params["textDocument"];
+                let uri = doc["uri"].as_str().unwrap_or_default();
+                let document = Document {
+                    text: doc["text"].as_str().unwrap_or_default().to_string(),
+                    lang: language(doc["languageId"].as_str().unwrap_or_default(), uri),
+                };
+                self.documents.insert(uri.to_string(), document);
+                vec![]
+            }
+            ("textDocument/didChange", None) => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                if let (Some(document), Some(text)) = (self.documents.get_mut(uri), text)
+                {
+                    document.text = text.to_string();
+                }
+                vec![]
+            }
+            ("textDocument/didClose", None) => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                self.documents.remove(uri);
+                vec![]
+            }
+            ("textDocument/codeAction", Some(id)) => {
+                vec![response(id, self.code_actions(params).into())]
+            }
+            (_, Some(id)) => vec![error_response(id, -32601, "Method not found")],
+            _ => vec![], // ignored notification
+        }
+    }
+    /// Compute the code actions available for the given range
+    fn code_actions(
+        &self,
+        params: &Value,
+    ) -> Vec<Value> {
+        let mut actions = Vec::new();
+        let Some(uri) = params["textDocument"]["uri"].as_str() else {
+            return actions;
+        };
+        let Some(document) = self.documents.get(uri) else {
+            return actions;
+        };
+        let Ok(list) = LocList::read_str(&document.text, document.lang) else {
+            return actions;
+        };
+        let selection = text_range(&params["range"]);
+        let lang = document.lang;
+        let mut candidates = Vec::new();
+        if let Ok(range) = list.range_around_line_number(selection.start.line) {
+            let sorted = sort_ranges(&list, lang, &[range]);
+            candidates.push(("Sort around cursor", "refactor.rewrite", sorted));
+        }
+        if !selection.is_single_line() {
+            let sorted = list
+                .snap_range(selection)
+                .and_then(|range| sort_ranges(&list, lang, &[range]));
+            candidates.push(("Sort selection", "refactor.rewrite", sorted));
+        } else if selection.start.column < selection.end.column {
+            let sorted = list.sort_in_line(selection, lang).map(|(sorted, _)| sorted);
+            candidates.push(("Sort selection", "refactor.rewrite", sorted));
+        }
+        let marked_ranges = list.marked_ranges();
+        if !marked_ranges.is_empty() {
+            let sorted = sort_ranges(&list, lang, &marked_ranges);
+            candidates.push(("Sort all marked lists", "source", sorted));
+        }
+        for (title, kind, sorted) in candidates {
+            let Ok(sorted) = sorted else {
+                continue;
+            };
+            let edits = TextEdit::between(&list, &sorted);
+            if edits.is_empty() {
+                continue;
+            }
+            actions.push(json!({
+                "title": title,
+                "kind": kind,
+                "edit": { "changes": { uri: lsp_edits(&edits) } },
+            }));
+        }
+        actions
+    }
+}
+
+#[test]
+fn test_scripted_session() {
+    let text =
+        "enum E {\n    B,\n    A,\n}\n\n// codesort: sort\nenum F {\n    D,\n    C,\n}\n";
+    let session = [
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": {
+                "uri": "file:///tmp/e.rs", "languageId": "rust", "version": 1, "text": text,
+            }},
+        }),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "textDocument/codeAction",
+            "params": {
+                "textDocument": { "uri": "file:///tmp/e.rs" },
+                "range": {
+                    "start": { "line": 1, "character": 4 },
+                    "end": { "line": 1, "character": 4 },
+                },
+                "context": { "diagnostics": [] },
+            },
+        }),
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+    ];
+    // messages go through the wire format
+    let mut input = Vec::new();
+    for message in &session {
+        write_message(&mut input, message).unwrap();
+    }
+    let mut reader = io::BufReader::new(input.as_slice());
+    let mut server = Server::default();
+    let mut responses = Vec::new();
+    while let Some(message) = read_message(&mut reader).unwrap() {
+        responses.extend(server.handle(&message));
+    }
+    assert!(server.exit);
+    assert_eq!(responses.len(), 3);
+    assert_eq!(
+        responses[0]["result"]["capabilities"]["codeActionProvider"],
+        true
+    );
+    let actions = responses[1]["result"].as_array().unwrap();
+    let titles: Vec<&str> = actions
+        .iter()
+        .map(|a| a["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, vec!["Sort around cursor", "Sort all marked lists"]);
+    let around_edits = &actions[0]["edit"]["changes"]["file:///tmp/e.rs"];
+    assert_eq!(
+        around_edits,
+        &json!([{
+            "range": {
+                "start": { "line": 1, "character": 0 },
+                "end": { "line": 3, "character": 0 },
+            },
+            "newText": "    A,\n    B,\n",
+        }])
+    );
+    let marked_edits = &actions[1]["edit"]["changes"]["file:///tmp/e.rs"];
+    assert_eq!(marked_edits[0]["newText"], "    C,\n    D,\n");
+    assert_eq!(responses[2]["id"], 3);
+}
@@ -1,4 +1,5 @@
 mod args;
+mod lsp;
 
 use {
     args::*,
@@ -6,10 +7,7 @@ use {
     codesort::*,
     std::{
         fs,
-        io::{
-            BufReader,
-            Write,
-        },
+        io::Write,
     },
 };
 
@@ -27,41 +25,467 @@ pub fn run() -> CsResult<()> {
         return Ok(());
     }
 
+    if let Some(Command::Lsp) = args.command {
+        return lsp::run();
+    }
+
     let src = args.src.as_ref().or(args.file.as_ref());
     let dst = args.dst.as_ref().or(args.file.as_ref());
-    let lang = args.lang();
 
     // Read input
-    let list = if let Some(src) = src {
-        let file = fs::File::open(src)?;
-        let reader = BufReader::new(file);
-        LocList::read(reader, lang)?
+    let input = if let Some(src) = src {
+        fs::read_to_string(src)?
     } else {
-        let stdin = std::io::stdin();
-        let reader = stdin.lock();
-        LocList::read(reader, lang)?
+        std::io::read_to_string(std::io::stdin())?
     };
 
-    // Focus the list to the area to sort
-    let focused = match (args.around, args.range) {
-        (Some(_), Some(_)) => {
-            return Err(CsError::RangeAndAround);
+    // Only describe what would be sorted
+    if args.blocks {
+        let list = read(&args, &input)?;
+        let options = args.sort_options();
+        for range in ranges(&args, &list)? {
+            for line in describe_blocks(&list.clone().focus(range)?, &options) {
+                println!("{line}");
+            }
         }
-        (Some(line), None) => list.focus_around_line_number(line)?,
-        (None, Some(range)) => list.focus(range)?,
-        _ => list.focus_all()?,
-    };
+        return Ok(());
+    }
+
+    // The JSON report is always written to stdout, the files being left untouched
+    if args.format == Format::Json {
+        let (json, result) = match sort(&args, &input) {
+            Ok((_, report)) => (serde_json::to_string_pretty(&report), Ok(())),
+            Err(e) => {
+                let error = serde_json::json!({ "error": e.to_string() });
+                (serde_json::to_string_pretty(&error), Err(e))
+            }
+        };
+        println!("{}", json?);
+        return result;
+    }
 
-    let sorted_list = focused.sort();
+    // When anything goes wrong, the input is written back unchanged, because
+    // editors replace the buffer with our output
+    let (output, result) = match sort(&args, &input) {
+        Ok((sorted_list, report)) => {
+            if let Some(cursor) = report.cursor {
+                eprintln!("cursor {}", cursor);
+            }
+            (sorted_list.to_string(), Ok(()))
+        }
+        Err(e) => (input, Err(e)),
+    };
 
     // Write output
     if let Some(dst) = dst {
         let file = fs::File::create(dst)?;
         let mut writer = std::io::BufWriter::new(file);
-        write!(&mut writer, "{}", sorted_list)?;
+        write!(&mut writer, "{}", output)?;
+    } else {
+        print!("{}", output);
+    }
+
+    result
+}
+
+fn read(
+    args: &Args,
+    input: &str,
+) -> CsResult<LocList> {
+    let lang = args.lang();
+    if args.lenient {
+        LocList::read_str_lenient(input, lang)
     } else {
-        print!("{}", sorted_list);
+        LocList::read_str(input, lang)
     }
+}
+
+/// Compute the ranges of lines to sort, in the order of the sorts
+///
+/// Single line ranges aren't included, as they don't sort lines but
+/// the items of a line.
+fn ranges(
+    args: &Args,
+    list: &LocList,
+) -> CsResult<Vec<LineNumberRange>> {
+    let mut ranges = Vec::new();
+    for &line in &args.around {
+        ranges.push(list.range_around_line_number(line)?);
+    }
+    for &range in &args.range {
+        if range.is_single_line() {
+            continue;
+        }
+        if range.has_columns() {
+            ranges.push(list.snap_range(range)?);
+        } else {
+            ranges.push(range.line_range());
+        }
+    }
+    if !args.all.is_empty() {
+        let mut selection = ItemSelection::new(args.all.clone());
+        if let Some(keywords) = &args.exclude_keywords {
+            selection.excluding_keywords =
+                keywords.iter().filter(|k| !k.is_empty()).cloned().collect();
+        }
+        ranges.extend(list.item_ranges(args.lang().analyzer(), &selection));
+    } else if args.around.is_empty() && args.range.is_empty() {
+        if args.lenient {
+            ranges.push(list.floor_range()?);
+        } else {
+            ranges.extend(list.full_range());
+        }
+    }
+    if args.recursive {
+        for range in ranges.clone() {
+            ranges.extend(list.nested_ranges(range, args.max_depth));
+        }
+    }
+    LineNumberRange::sort_order(&ranges)
+}
 
-    Ok(())
+/// Sort and check every result, return the sorted list and the report
+/// of the sorts
+fn sort(
+    args: &Args,
+    input: &str,
+) -> CsResult<(LocList, SortReport)> {
+    let lang = args.lang();
+    if args.verify && lang.analyzer() != Analyzer::Rust {
+        return Err(CsError::VerifyNotSupported(lang));
+    }
+    let original = read(args, input)?;
+    let mut list = original.clone();
+    let mut report = SortReport::default();
+    let mut mapping = LineMapping::identity(list.len());
+    let options = args.sort_options();
+    let mut cursor = args.cursor;
+    if let Some(cursor) = cursor {
+        original.check_position(cursor)?;
+    }
+    // Sorting the items of a line moves no line, so it doesn't change
+    // the ranges, which are computed after
+    for &range in args.range.iter().filter(|r| r.is_single_line()) {
+        let (sorted_list, moves) = list.sort_in_line(range, lang)?;
+        list = sorted_list;
+        cursor = cursor.map(|cursor| cursor.moved_in_line(range.start.line, &moves));
+        report.ranges.push(range.line_range());
+    }
+    let mut ranges = ranges(args, &list)?;
+    // The report refers to the lines of the input, for the old positions,
+    // and to the lines of the output, for the new ones, while the sorts
+    // work on the current list, whose lines may have been moved, removed,
+    // or added. `mapping` goes from the input to the current list, and the
+    // mapping after every sort is kept to find the output lines at the end.
+    let mut sorts_mappings = Vec::new();
+    for i in 0..ranges.len() {
+        let range = ranges[i];
+        let line_count = list.len();
+        if args.explicit_discriminants {
+            list.make_discriminants_explicit(range, lang)?;
+        }
+        if let Err(e) = list.check_order_independent(range, lang.analyzer()) {
+            if !args.force && args.all.is_empty() {
+                return Err(e);
+            }
+            // with --all, the lists whose order matters are skipped
+            let warning = if args.force {
+                e.to_string()
+            } else {
+                format!("skipped {}: {e}", mapping.old_range(range))
+            };
+            if args.format == Format::Code {
+                eprintln!("Warning: {warning}");
+            }
+            report.warnings.push(warning);
+            if !args.force {
+                continue;
+            }
+        }
+        let mut focus_range = range;
+        if args.warn_duplicates || args.dedupe {
+            let duplicates = if args.dedupe {
+                list.remove_duplicates(range)
+            } else {
+                list.duplicates(range)
+            };
+            let removed: Vec<LineNumberRange> =
+                duplicates.iter().map(|d| d.duplicate).collect();
+            let duplicates: Vec<Duplicate> = duplicates
+                .into_iter()
+                .map(|d| Duplicate {
+                    original: mapping.old_range(d.original),
+                    duplicate: mapping.old_range(d.duplicate),
+                })
+                .collect();
+            if args.format == Format::Code {
+                for duplicate in &duplicates {
+                    let prefix = if args.dedupe { "Removed" } else { "Warning" };
+                    eprintln!("{prefix}: {duplicate}");
+                }
+            }
+            if args.dedupe && !removed.is_empty() {
+                mapping = mapping.then(&LineMapping::removing(line_count, &removed));
+                let end = range.end.to_index() + list.len() - line_count;
+                focus_range.end = LineNumber::from_index(end);
+            }
+            report.duplicates.extend(duplicates);
+        }
+        let unsorted_count = list.len();
+        let focused = list.focus(focus_range)?;
+        let (sorted_list, moves) = focused.clone().sort_with(&options);
+        focused.check_sorted_with(&sorted_list, lang, &options)?;
+        if args.verify {
+            focused.verify_sorted(&sorted_list)?;
+        }
+        let (first_range, first_block) = (report.ranges.len(), report.blocks.len());
+        report.add_sort(&focused, moves.clone());
+        for range in &mut report.ranges[first_range..] {
+            *range = mapping.old_range(*range);
+        }
+        for block in &mut report.blocks[first_block..] {
+            block.old_range = mapping.old_range(block.old_range);
+        }
+        mapping = mapping.then(&LineMapping::new(unsorted_count, &moves));
+        sorts_mappings.push((first_block..report.blocks.len(), mapping.clone()));
+        // removing duplicates or normalizing the spacing may change the
+        // number of lines, which moves the end of the enclosing ranges,
+        // sorted after this one
+        if sorted_list.len() != line_count {
+            for enclosing in ranges[i + 1..].iter_mut() {
+                if enclosing.includes(range) {
+                    let end = enclosing.end.to_index() + sorted_list.len() - line_count;
+                    enclosing.end = LineNumber::from_index(end);
+                }
+            }
+        }
+        list = sorted_list;
+    }
+    for (blocks, sort_mapping) in sorts_mappings {
+        for block in &mut report.blocks[blocks] {
+            block.new_range = mapping.new_range(sort_mapping.old_range(block.new_range));
+        }
+    }
+    report.edits = TextEdit::between(&original, &list);
+    report.cursor = cursor.map(|cursor| cursor.mapped(&mapping));
+    Ok((list, report))
+}
+
+/// Describe the range of the focus, then the range and the keys of every
+/// block, as compared by the sort, and the comments which don't move with
+/// a block
+fn describe_blocks(
+    focused: &Focused,
+    options: &SortOptions,
+) -> Vec<String> {
+    let Some(focus_range) = focused.focus_range() else {
+        return vec!["focus empty".to_string()];
+    };
+    let mut lines = vec![format!("focus {}", focus_range)];
+    let blocks = focused.focus.clone().into_blocks();
+    let groups = if options.groups {
+        Group::split(blocks)
+    } else {
+        vec![Group {
+            header: LocList::default(),
+            blocks,
+        }]
+    };
+    let mut start = focused.before.len();
+    let range = |start: usize, len: usize| LineNumberRange {
+        start: LineNumber::from_index(start),
+        end: LineNumber::from_index(start + len - 1),
+    };
+    for group in groups {
+        if !group.header.is_empty() {
+            lines.push(format!("header {}", range(start, group.header.len())));
+            start += group.header.len();
+        }
+        // the comments moving with a block are shown in its range
+        let mut block_start = None;
+        for piece in options.comments.split(group.blocks) {
+            let len = piece.list.len();
+            match piece.placement {
+                Placement::Sorted => {
+                    let block_start = block_start.take().unwrap_or(start);
+                    let pin = match piece.pin {
+                        Pin::First => "pin-first ",
+                        Pin::Unpinned => "",
+                        Pin::Last => "pin-last ",
+                    };
+                    lines.push(format!(
+                        "block {} {}{}",
+                        range(block_start, start + len - block_start),
+                        pin,
+                        options.comparator.describe_key(&piece.list),
+                    ));
+                }
+                Placement::WithBlock(_) => {
+                    block_start.get_or_insert(start);
+                }
+                Placement::InPlace(_) => {
+                    lines.push(format!("comments {} in-place", range(start, len)));
+                }
+                Placement::AtEnd => {
+                    lines.push(format!("comments {} at-end", range(start, len)));
+                }
+            }
+            start += len;
+        }
+    }
+    lines
+}
+
+#[test]
+fn test_json_report_with_dedupe() {
+    let input = "enum Choice {\n    Beta,\n    Alpha,\n    Beta,\n    /// the first\n    Aaa {\n        x: u8,\n    },\n}\n";
+    let args =
+        Args::parse_from(["codesort", "--around", "2", "--dedupe", "--format", "json"]);
+    let (_, report) = sort(&args, input).unwrap();
+    let report = serde_json::to_value(&report).unwrap();
+    let range =
+        |start: usize, end: usize| serde_json::json!({ "start": start, "end": end });
+    assert_eq!(report["ranges"], serde_json::json!([range(2, 8)]));
+    assert_eq!(
+        report["duplicates"],
+        serde_json::json!([{ "original": range(2, 2), "duplicate": range(4, 4) }]),
+    );
+    // old ranges are lines of the input, new ranges lines of the output
+    let moves: Vec<_> = report["blocks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|block| (block["old_range"].clone(), block["new_range"].clone()))
+        .collect();
+    assert_eq!(
+        moves,
+        vec![
+            (range(5, 8), range(2, 5)),
+            (range(3, 3), range(6, 6)),
+            (range(2, 2), range(7, 7)),
+        ],
+    );
+}
+
+#[test]
+fn test_json_report_of_several_sorts() {
+    // the sort of the struct, normalizing the spacing, removes a line
+    let input =
+        "struct A {\n    z: u8,\n\n\n    y: u8,\n}\nenum B {\n    D,\n    C,\n}\n";
+    let args = Args::parse_from([
+        "codesort",
+        "--around",
+        "2",
+        "--around",
+        "8",
+        "--spacing",
+        "normalize",
+        "--format",
+        "json",
+    ]);
+    let (sorted, report) = sort(&args, input).unwrap();
+    assert_eq!(
+        sorted.to_string(),
+        "struct A {\n    y: u8,\n\n    z: u8,\n}\nenum B {\n    C,\n    D,\n}\n",
+    );
+    let report = serde_json::to_value(&report).unwrap();
+    let range =
+        |start: usize, end: usize| serde_json::json!({ "start": start, "end": end });
+    // the enum, sorted first, is then moved up by the sort of the struct
+    assert_eq!(
+        report["ranges"],
+        serde_json::json!([range(8, 9), range(2, 5)])
+    );
+    let moves: Vec<_> = report["blocks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|block| (block["old_range"].clone(), block["new_range"].clone()))
+        .collect();
+    assert_eq!(moves[0], (range(9, 9), range(7, 7)));
+    assert_eq!(moves[1], (range(8, 8), range(8, 8)));
+}
+
+#[test]
+fn test_cursor() {
+    let input = "use std::{io, fs};\nenum E {\n    B,\n    A,\n}\n";
+    let cursor = |cursor: &str| {
+        let args = Args::parse_from(["codesort", "--range", "1", "--cursor", cursor]);
+        sort(&args, input).map(|(_, report)| report.cursor.unwrap().to_string())
+    };
+    // the cursor follows the item it's in, and stays on the separators
+    assert_eq!(cursor("1:12").unwrap(), "1:16");
+    assert_eq!(cursor("1:13").unwrap(), "1:13");
+    assert_eq!(cursor("3:6").unwrap(), "3:6");
+    // the cursor follows its line when it's in a sorted range
+    let args = Args::parse_from(["codesort", "--around", "3", "--cursor", "3:6"]);
+    let (_, report) = sort(&args, input).unwrap();
+    assert_eq!(report.cursor.unwrap().to_string(), "4:6");
+    // a column just after the end of the line is in the input
+    assert!(cursor("1:19").is_ok());
+    assert!(matches!(
+        cursor("1:20"),
+        Err(CsError::PositionOutOfInput(_))
+    ));
+    assert!(matches!(cursor("6"), Err(CsError::PositionOutOfInput(_))));
+}
+
+#[test]
+fn test_all_skips_order_dependent_lists() {
+    let input = "enum A {\n    D,\n    C,\n}\n#[derive(PartialOrd)]\nenum B {\n    Y {\n        b: u8,\n        a: u8,\n    },\n    X,\n}\n";
+    let args = Args::parse_from([
+        "codesort",
+        "--all",
+        "enum",
+        "--recursive",
+        "--exclude-keywords",
+        "serde",
+    ]);
+    let (sorted, report) = sort(&args, input).unwrap();
+    assert_eq!(
+        sorted.to_string(),
+        input.replace("    D,\n    C,\n", "    C,\n    D,\n"),
+    );
+    // both the variants and the fields of a variant of B are skipped
+    assert_eq!(report.warnings.len(), 2);
+    assert!(report.warnings.iter().all(|w| w.starts_with("skipped ")));
+    // without --all, a list whose order matters is an error
+    let args = Args::parse_from(["codesort", "--around", "8"]);
+    assert!(matches!(
+        sort(&args, input),
+        Err(CsError::OrderDependent(_))
+    ));
+}
+
+#[test]
+fn test_describe_blocks() {
+    let input = "enum E {\n    // anything\n    Zeta,\n    Alpha,\n\n    // detached\n    Beta,\n    // trailing\n}\n";
+    let describe = |args: &[&str]| {
+        let args = Args::parse_from(["codesort", "--range", "2:8"].iter().chain(args));
+        let list = read(&args, input).unwrap();
+        let range = ranges(&args, &list).unwrap()[0];
+        describe_blocks(&list.focus(range).unwrap(), &args.sort_options())
+    };
+    assert_eq!(
+        describe(&[]),
+        vec![
+            "focus 2:8",
+            "block 2:3 Zeta,",
+            "block 4:4 Alpha,",
+            "block 5:7 Beta,",
+            "comments 8:8 at-end",
+        ],
+    );
+    assert_eq!(
+        describe(&["--order", "Beta", "--leading-comments", "in-place"]),
+        vec![
+            "focus 2:8",
+            "comments 2:2 in-place",
+            "block 3:3 Zeta,",
+            "block 4:4 Alpha,",
+            "comments 5:6 in-place",
+            "block 7:7 order=1 Beta,",
+            "comments 8:8 at-end",
+        ],
+    );
 }
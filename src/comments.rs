@@ -0,0 +1,195 @@
+use {
+    crate::*,
+    std::str::FromStr,
+};
+
+/// Where a comment goes when the blocks around it are sorted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommentPolicy {
+    /// Move with the block the comment is attached to: the next one, or
+    /// the last one for a comment at the end of the list
+    #[default]
+    WithBlock,
+    /// Stay at the same position among the blocks
+    InPlace,
+    /// Go to the end of the list
+    AtEnd,
+}
+
+/// How the comments which aren't inside the code of a block are attached
+/// to the blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentAttachment {
+    /// Comments directly above a block, like doc comments
+    pub leading: CommentPolicy,
+    /// Comments separated from the next block by a blank line, like
+    /// section comments
+    pub detached: CommentPolicy,
+    /// Comments after the last block of the list
+    pub trailing: CommentPolicy,
+}
+
+/// Where a piece of the focus goes when sorting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// The piece holds the code of a block, and is sorted
+    Sorted,
+    /// The piece goes right before the code of the block of this index,
+    /// wherever it's sorted
+    WithBlock(usize),
+    /// The piece stays before the block sorted at this index
+    InPlace(usize),
+    AtEnd,
+}
+
+/// A part of a focus which moves as a whole: a block, or comments
+/// detached from it
+#[derive(Debug, Clone)]
+pub struct Piece {
+    pub list: LocList,
+    pub placement: Placement,
+    /// The pin of the whole block the piece comes from
+    pub pin: Pin,
+}
+
+impl Default for CommentAttachment {
+    fn default() -> Self {
+        Self {
+            leading: CommentPolicy::WithBlock,
+            detached: CommentPolicy::WithBlock,
+            trailing: CommentPolicy::AtEnd,
+        }
+    }
+}
+
+impl CommentAttachment {
+    /// Split the blocks of a focus into pieces, according to the policies.
+    ///
+    /// The pieces are returned in their original order, so that they may
+    /// be put back together when nothing is sorted.
+    pub fn split(
+        self,
+        blocks: Vec<LocList>,
+    ) -> Vec<Piece> {
+        let mut pieces: Vec<Piece> = Vec::new();
+        let mut sorted_count = 0;
+        for block in blocks {
+            let pin = block.pin();
+            let Some(code_start) = block
+                .locs
+                .iter()
+                .position(|loc| loc.is_sortable() || loc.is_annotation)
+            else {
+                // comments, or blank lines, after the last block
+                let last_sorted = pieces
+                    .last_mut()
+                    .filter(|piece| piece.placement == Placement::Sorted);
+                match (self.trailing, last_sorted) {
+                    (CommentPolicy::WithBlock, Some(last)) => {
+                        last.list.locs.extend(block.locs);
+                    }
+                    (CommentPolicy::AtEnd, _) => {
+                        pieces.push(Piece::at_end(block, pin));
+                    }
+                    _ => {
+                        pieces.push(Piece {
+                            list: block,
+                            placement: Placement::InPlace(sorted_count),
+                            pin,
+                        });
+                    }
+                }
+                continue;
+            };
+            let locs = &block.locs;
+            let leading_start = code_start
+                - locs[..code_start]
+                    .iter()
+                    .rev()
+                    .take_while(|loc| !loc.is_blank())
+                    .count();
+            let detached_start = block.count_blank_lines_at_start().min(leading_start);
+            // the parts of the block before its code: detached comments
+            // with the blank lines around them, then leading comments
+            let mut parts = Vec::new();
+            if detached_start < leading_start {
+                parts.push((leading_start, self.detached));
+            }
+            if leading_start < code_start {
+                parts.push((code_start, self.leading));
+            }
+            // the parts moving with the block just before its code stay in it
+            while parts
+                .last()
+                .map_or(false, |&(_, policy)| policy == CommentPolicy::WithBlock)
+            {
+                parts.pop();
+            }
+            let mut locs = block.locs;
+            let mut consumed = 0;
+            for (part_end, policy) in parts {
+                let rest = locs.split_off(part_end - consumed);
+                let part = LocList {
+                    locs: std::mem::replace(&mut locs, rest),
+                    lang: None,
+                };
+                consumed = part_end;
+                let piece = match policy {
+                    CommentPolicy::WithBlock => Piece {
+                        list: part,
+                        placement: Placement::WithBlock(sorted_count),
+                        pin,
+                    },
+                    CommentPolicy::InPlace => Piece {
+                        list: part,
+                        placement: Placement::InPlace(sorted_count),
+                        pin,
+                    },
+                    CommentPolicy::AtEnd => Piece::at_end(part, pin),
+                };
+                pieces.push(piece);
+            }
+            pieces.push(Piece {
+                list: LocList { locs, lang: None },
+                placement: Placement::Sorted,
+                pin,
+            });
+            sorted_count += 1;
+        }
+        pieces
+    }
+}
+
+impl Piece {
+    /// Make a piece going to the end of the list, its blank lines being
+    /// put before its comments
+    fn at_end(
+        mut list: LocList,
+        pin: Pin,
+    ) -> Self {
+        let blank_count = list
+            .locs
+            .iter()
+            .rev()
+            .take_while(|loc| loc.is_blank())
+            .count();
+        list.locs.rotate_right(blank_count);
+        Self {
+            list,
+            placement: Placement::AtEnd,
+            pin,
+        }
+    }
+}
+
+impl FromStr for CommentPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "with-block" => Ok(Self::WithBlock),
+            "in-place" => Ok(Self::InPlace),
+            "at-end" => Ok(Self::AtEnd),
+            _ => Err(format!("Unknown comment policy: {}", s)),
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use {
+    crate::*,
+    lazy_regex::Regex,
+    std::{
+        cmp::Ordering,
+        str::FromStr,
+    },
+};
+
+/// A way to compare two blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Criterion {
+    /// The rank of the identifier of the blocks in a list of names, blocks
+    /// whose identifier isn't in the list coming after
+    Precedence(Vec<String>),
+    /// The keys extracted from the blocks by a regular expression
+    Key(KeyRegex),
+    /// The types of the Rust fields declared by the blocks, or their
+    /// alignments
+    Field(FieldKey),
+    /// The categories of the members of a Rust `impl` or `trait` declared
+    /// by the blocks, in the given order, then their names
+    Members(Vec<MemberCategory>),
+    /// The sort keys of the lines of the blocks, which exclude comments,
+    /// whitespaces, and annotations
+    SortKey,
+    /// The text of the annotations of the blocks (eg `#[cfg(unix)]`)
+    Annotations,
+    /// The content of the non blank lines of the blocks, including comments
+    Content,
+}
+
+/// Compares blocks with a chain of criteria, each criterion being used
+/// only when the blocks are equal according to the previous ones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockComparator {
+    pub criteria: Vec<Criterion>,
+}
+
+impl Default for BlockComparator {
+    /// Compare by sort key, then by annotation text, then by whole content,
+    /// so that the order of blocks never depends on their initial order
+    /// (unless they're identical)
+    fn default() -> Self {
+        Self {
+            criteria: vec![
+                Criterion::SortKey,
+                Criterion::Annotations,
+                Criterion::Content,
+            ],
+        }
+    }
+}
+
+impl BlockComparator {
+    pub fn new(criteria: Vec<Criterion>) -> Self {
+        Self { criteria }
+    }
+    /// Make the default comparator, the blocks being first compared with
+    /// the given criteria, in order
+    pub fn with_criteria(criteria: Vec<Criterion>) -> Self {
+        let mut comparator = Self::default();
+        comparator.criteria.splice(0..0, criteria);
+        comparator
+    }
+    /// Make the default comparator, with the blocks whose identifier is
+    /// in the list coming first, in the order of the list
+    pub fn with_precedence(names: Vec<String>) -> Self {
+        Self::with_criteria(vec![Criterion::Precedence(names)])
+    }
+    /// Make the default comparator, the blocks being first compared on
+    /// the keys extracted with the regular expression
+    pub fn with_key(regex: KeyRegex) -> Self {
+        Self::with_criteria(vec![Criterion::Key(regex)])
+    }
+    pub fn compare(
+        &self,
+        a: &LocList,
+        b: &LocList,
+    ) -> Ordering {
+        self.criteria
+            .iter()
+            .map(|criterion| criterion.compare(a, b))
+            .find(|&ordering| ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    }
+    /// Describe the keys the blocks are compared on, criterion after
+    /// criterion, for diagnostics
+    pub fn describe_key(
+        &self,
+        block: &LocList,
+    ) -> String {
+        self.criteria
+            .iter()
+            .filter_map(|criterion| criterion.describe_key(block))
+            .filter(|key| !key.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Criterion {
+    pub fn compare(
+        &self,
+        a: &LocList,
+        b: &LocList,
+    ) -> Ordering {
+        match self {
+            Self::Precedence(names) => {
+                let rank = |block: &LocList| {
+                    block
+                        .identifier()
+                        .and_then(|identifier| {
+                            names.iter().position(|name| *name == identifier)
+                        })
+                        .unwrap_or(names.len())
+                };
+                rank(a).cmp(&rank(b))
+            }
+            Self::Key(regex) => regex.key(a).cmp(&regex.key(b)),
+            Self::Field(key) => key.compare(a, b),
+            Self::Members(order) => compare_members(order, a, b),
+            Self::SortKey => a.cmp(b),
+            Self::Annotations => annotations(a).cmp(annotations(b)),
+            Self::Content => non_blank_lines(a).cmp(non_blank_lines(b)),
+        }
+    }
+    /// Describe the key of the block this criterion compares, if it's
+    /// not the whole content
+    pub fn describe_key(
+        &self,
+        block: &LocList,
+    ) -> Option<String> {
+        match self {
+            Self::Precedence(names) => {
+                let identifier = block.identifier()?;
+                let rank = names.iter().position(|name| *name == identifier)?;
+                Some(format!("order={}", rank + 1))
+            }
+            Self::Key(regex) => Some(format!("key={}", regex.key(block))),
+            Self::Field(key) => {
+                let field = Field::from_block(block)?;
+                match key {
+                    FieldKey::Type => Some(format!("type={}", field.ty)),
+                    FieldKey::Alignment => {
+                        let alignment = field.alignment()?;
+                        Some(format!("alignment={alignment}"))
+                    }
+                }
+            }
+            Self::Members(_) => {
+                let member = Member::from_block(block)?;
+                Some(format!("member={}", member.category))
+            }
+            Self::SortKey => block.sort_key().map(String::from),
+            Self::Annotations => Some(annotations(block).collect::<Vec<_>>().join(" ")),
+            Self::Content => None,
+        }
+    }
+}
+
+/// A regular expression extracting the sort key of a block: the capture
+/// named `key` (or the whole match when there's no such group) in the
+/// first sortable line of the block which matches.
+///
+/// Blocks without matching line are keyed by their sort key.
+#[derive(Debug, Clone)]
+pub struct KeyRegex {
+    regex: Regex,
+}
+
+impl KeyRegex {
+    pub fn key(
+        &self,
+        block: &LocList,
+    ) -> String {
+        let sortable_locs = || block.locs.iter().filter(|loc| loc.is_sortable());
+        sortable_locs()
+            .find_map(|loc| {
+                let captures = self.regex.captures(loc.content.trim())?;
+                let key = captures.name("key").or_else(|| captures.get(0))?;
+                Some(key.as_str().to_string())
+            })
+            .unwrap_or_else(|| sortable_locs().map(|loc| loc.sort_key.as_str()).collect())
+    }
+}
+
+impl FromStr for KeyRegex {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let regex = Regex::new(s).map_err(|e| format!("Invalid key regex: {}", e))?;
+        Ok(Self { regex })
+    }
+}
+
+impl PartialEq for KeyRegex {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+    }
+}
+
+impl Eq for KeyRegex {}
+
+fn annotations(block: &LocList) -> impl Iterator<Item = &str> {
+    block
+        .locs
+        .iter()
+        .filter(|loc| loc.is_annotation)
+        .map(|loc| loc.content.trim())
+}
+
+fn non_blank_lines(block: &LocList) -> impl Iterator<Item = &str> {
+    block
+        .locs
+        .iter()
+        .filter(|loc| !loc.is_blank())
+        .map(|loc| loc.content.trim())
+}
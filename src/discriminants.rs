@@ -0,0 +1,175 @@
+use {
+    crate::*,
+    lazy_regex::regex_captures,
+};
+
+/// Follows the discriminants of the variants of a C-like enum, in order
+/// of declaration, to write the implicit ones
+#[derive(Debug, Clone, Default)]
+pub struct Discriminants {
+    /// The last discriminant, as an expression plus an offset, if any
+    /// variant was seen
+    last: Option<Discriminant>,
+}
+
+#[derive(Debug, Clone)]
+struct Discriminant {
+    /// The last explicit discriminant, when it's not an integer literal
+    base: Option<String>,
+    offset: i128,
+    /// Whether the last explicit discriminant was written in hexadecimal
+    hex: bool,
+}
+
+impl Discriminants {
+    /// Take into account the variant declared in the line, and return the
+    /// line with its discriminant made explicit.
+    ///
+    /// Return `None` when the line isn't the declaration of a unit variant
+    pub fn make_explicit(
+        &mut self,
+        line: &str,
+    ) -> Option<String> {
+        let tokens = rust::tokenize(line);
+        let mut code_tokens = tokens
+            .iter()
+            .filter(|token| !token.starts_with("//") && !token.starts_with("/*"));
+        let name = code_tokens.next()?;
+        if !is_identifier(name) {
+            return None;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if !line[indent..].starts_with(name.as_str()) {
+            return None;
+        }
+        let name_end = indent + name.len();
+        match code_tokens.next().map(String::as_str) {
+            None | Some(",") => {
+                if code_tokens.next().is_some() {
+                    return None;
+                }
+                let value = self.next_implicit();
+                Some(format!(
+                    "{} = {}{}",
+                    &line[..name_end],
+                    value,
+                    &line[name_end..]
+                ))
+            }
+            Some("=") => {
+                let comment_start = tokens
+                    .iter()
+                    .filter(|token| token.starts_with("//") || token.starts_with("/*"))
+                    .filter_map(|comment| line.rfind(comment.as_str()))
+                    .min()
+                    .unwrap_or(line.len());
+                let code = &line[name_end..comment_start];
+                let expr = code.trim().strip_prefix('=')?;
+                let expr = expr.trim_end().trim_end_matches(',').trim();
+                self.last = Some(Discriminant::explicit(expr));
+                Some(line.to_string())
+            }
+            _ => None,
+        }
+    }
+    fn next_implicit(&mut self) -> String {
+        let discriminant = match self.last.take() {
+            Some(mut d) => {
+                d.offset += 1;
+                d
+            }
+            None => Discriminant {
+                base: None,
+                offset: 0,
+                hex: false,
+            },
+        };
+        let value = discriminant.to_string();
+        self.last = Some(discriminant);
+        value
+    }
+}
+
+impl Discriminant {
+    fn explicit(expr: &str) -> Self {
+        let literal = regex_captures!(
+            r"^(-?)(?:0x([0-9a-fA-F_]+)|([0-9_]+))(?:[iu](?:8|16|32|64|128|size)|[uUlL]*)$",
+            expr
+        );
+        if let Some((_, sign, hex, dec)) = literal {
+            let (digits, radix) = if hex.is_empty() { (dec, 10) } else { (hex, 16) };
+            if let Ok(value) = i128::from_str_radix(&digits.replace('_', ""), radix) {
+                return Self {
+                    base: None,
+                    offset: if sign.is_empty() { value } else { -value },
+                    hex: radix == 16,
+                };
+            }
+        }
+        let base = if expr
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == ':')
+        {
+            expr.to_string()
+        } else {
+            format!("({expr})")
+        };
+        Self {
+            base: Some(base),
+            offset: 0,
+            hex: false,
+        }
+    }
+}
+
+impl std::fmt::Display for Discriminant {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match &self.base {
+            Some(base) => write!(f, "{} + {}", base, self.offset),
+            None if self.hex && self.offset >= 0 => write!(f, "0x{:X}", self.offset),
+            None => write!(f, "{}", self.offset),
+        }
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    s.chars()
+        .next()
+        .map_or(false, |c| c.is_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[test]
+fn test_make_discriminants_explicit() {
+    let lines = [
+        "    A,\n",
+        "    B = 4, // four\n",
+        "    C, // after four\n",
+        "    D = 0x10,\n",
+        "    E\n",
+        "    F = BASE << 2,\n",
+        "    G,\n",
+    ];
+    let mut discriminants = Discriminants::default();
+    let new_lines: Vec<String> = lines
+        .iter()
+        .map(|line| discriminants.make_explicit(line).unwrap())
+        .collect();
+    assert_eq!(
+        new_lines,
+        vec![
+            "    A = 0,\n",
+            "    B = 4, // four\n",
+            "    C = 5, // after four\n",
+            "    D = 0x10,\n",
+            "    E = 0x11\n",
+            "    F = BASE << 2,\n",
+            "    G = (BASE << 2) + 1,\n",
+        ]
+    );
+    assert_eq!(discriminants.make_explicit("    Found {\n"), None);
+    assert_eq!(discriminants.make_explicit("    Tuple(u8),\n"), None);
+}
@@ -15,20 +15,35 @@ pub enum CsError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
     #[error("No sortable range found around line {}", .0+1)]
     NoSortableRangeAround(LineIndex),
 
-    #[error("You can't specify both --around and --range")]
-    RangeAndAround,
+    #[error("The order of this list matters: {0}")]
+    OrderDependent(String),
+
+    #[error("Ranges {0} and {1} overlap without one being inside the other")]
+    OverlappingRanges(LineNumberRange, LineNumberRange),
+
+    #[error("Position {0} is out of the input")]
+    PositionOutOfInput(TextPosition),
 
     #[error("Provided range not sortable (lang: {0:?})")]
     RangeNotSortable(Language),
 
+    #[error("Sort result rejected: {0}")]
+    SortRejected(String),
+
     #[error("Unclosed char literal at line {}", .0+1)]
     UnclosedCharLiteral(LineIndex),
 
     #[error("Unexpected closing brace: {0}")]
     UnexpectedClosingBrace(char),
+
+    #[error("--verify isn't supported for {0:?} code")]
+    VerifyNotSupported(Language),
 }
 
 pub type CsResult<T> = std::result::Result<T, CsError>;
@@ -0,0 +1,171 @@
+use {
+    crate::*,
+    std::{
+        cmp::Ordering,
+        str::FromStr,
+    },
+};
+
+/// A key to sort Rust struct fields or function parameters, which are
+/// blocks like `name: Type,`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKey {
+    /// The type of the field, so that fields of the same type are together
+    Type,
+    /// The alignment of the type, in descending order to reduce padding,
+    /// fields of other types than primitive ones coming last
+    Alignment,
+}
+
+/// A struct field, or a function parameter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    /// The type, as written, without spaces between symbols
+    pub ty: String,
+}
+
+impl FieldKey {
+    /// Compare the blocks, a `self` receiver coming first, and the blocks
+    /// which aren't fields coming last
+    pub fn compare(
+        self,
+        a: &LocList,
+        b: &LocList,
+    ) -> Ordering {
+        let (field_a, field_b) = (Field::from_block(a), Field::from_block(b));
+        let rank = |block: &LocList, field: &Option<Field>| {
+            if is_receiver(block) {
+                0
+            } else if field.is_some() {
+                1
+            } else {
+                2
+            }
+        };
+        let by_rank = rank(a, &field_a).cmp(&rank(b, &field_b));
+        match (field_a, field_b) {
+            (Some(a), Some(b)) if by_rank == Ordering::Equal => match self {
+                Self::Type => a.ty.cmp(&b.ty),
+                Self::Alignment => match (a.alignment(), b.alignment()) {
+                    (Some(a), Some(b)) => b.cmp(&a),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                },
+            },
+            _ => by_rank,
+        }
+    }
+}
+
+impl FromStr for FieldKey {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "type" => Ok(Self::Type),
+            "alignment" => Ok(Self::Alignment),
+            _ => Err(format!("Unknown field key: {}", s)),
+        }
+    }
+}
+
+impl Field {
+    /// Read the field declared by a block, attributes and comments being
+    /// ignored
+    pub fn from_block(block: &LocList) -> Option<Self> {
+        let tokens = block.code_tokens();
+        let mut i = 0;
+        if tokens.first().map_or(false, |t| t == "pub") {
+            i += 1;
+            if tokens.get(i).map_or(false, |t| t == "(") {
+                i += tokens[i..].iter().position(|t| t == ")")? + 1;
+            }
+        }
+        while tokens.get(i).map_or(false, |t| t == "mut") {
+            i += 1;
+        }
+        let name = tokens.get(i)?;
+        if !name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            return None;
+        }
+        if tokens.get(i + 1).map_or(true, |t| t != ":")
+            || tokens.get(i + 2).map_or(false, |t| t == ":")
+        {
+            return None;
+        }
+        let mut type_tokens = &tokens[i + 2..];
+        if let Some((last, rest)) = type_tokens.split_last() {
+            if last == "," {
+                type_tokens = rest;
+            }
+        }
+        if type_tokens.is_empty() {
+            return None;
+        }
+        let mut ty = String::new();
+        for token in type_tokens {
+            let is_word =
+                |s: &str| s.ends_with(|c: char| c.is_alphanumeric() || c == '_');
+            if is_word(&ty)
+                && token.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+            {
+                ty.push(' ');
+            }
+            ty.push_str(token);
+        }
+        Some(Self {
+            name: name.to_string(),
+            ty,
+        })
+    }
+    /// The alignment of the type, in bytes, when it's a primitive type,
+    /// or an array of a primitive type (assuming a 64 bits platform)
+    pub fn alignment(&self) -> Option<usize> {
+        let mut ty = self.ty.as_str();
+        while let Some(element) = ty.strip_prefix('[') {
+            ty = element.split(';').next()?;
+        }
+        let alignment = match ty {
+            "bool" | "i8" | "u8" => 1,
+            "i16" | "u16" => 2,
+            "char" | "f32" | "i32" | "u32" => 4,
+            "f64" | "i64" | "isize" | "u64" | "usize" => 8,
+            "i128" | "u128" => 16,
+            _ => return None,
+        };
+        Some(alignment)
+    }
+}
+
+/// Tell whether the block is the `self` parameter of a method, like
+/// `&mut self,` or `self: Box<Self>`
+fn is_receiver(block: &LocList) -> bool {
+    let tokens = block.code_tokens();
+    tokens
+        .iter()
+        .map(String::as_str)
+        .find(|&t| t != "&" && t != "mut" && !t.starts_with('\''))
+        == Some("self")
+}
+
+#[test]
+fn test_read_field() {
+    let field = |code: &str| {
+        let block = LocList::read_str(code, Language::Rust).unwrap();
+        Field::from_block(&block).map(|field| (field.name, field.ty))
+    };
+    let some = |name: &str, ty: &str| Some((name.to_string(), ty.to_string()));
+    assert_eq!(field("count: u32,"), some("count", "u32"));
+    assert_eq!(
+        field("pub(crate) names: Vec<String>, // sorted"),
+        some("names", "Vec<String>")
+    );
+    assert_eq!(
+        field("#[serde(default)]\npub buffer: [u8; 16],"),
+        some("buffer", "[u8;16]")
+    );
+    assert_eq!(field("label: &'static str"), some("label", "&'static str"));
+    assert_eq!(field("Level::Warn => 3,"), None);
+    assert_eq!(field("Found { pos: usize },"), None);
+}
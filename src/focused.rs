@@ -1,10 +1,15 @@
-use crate::*;
+use {
+    crate::*,
+    std::cmp::Ordering,
+};
 
 #[derive(Debug, Clone)]
 pub struct Focused {
     pub before: LocList,
     pub focus: LocList,
     pub after: LocList,
+    /// The language the list was read with, when known
+    pub lang: Option<Language>,
 }
 
 impl Focused {
@@ -14,15 +19,382 @@ impl Focused {
         self.after.print_debug(" AFTER ");
     }
     pub fn sort(self) -> LocList {
+        self.sort_with_moves().0
+    }
+    /// Sort, and also return where every block of the focus went
+    pub fn sort_with_moves(self) -> (LocList, Vec<BlockMove>) {
+        self.sort_with(&SortOptions::default())
+    }
+    /// Sort, comparing the blocks with the given comparator instead
+    /// of the default one
+    pub fn sort_by(
+        self,
+        comparator: BlockComparator,
+    ) -> LocList {
+        let options = SortOptions {
+            comparator,
+            ..Default::default()
+        };
+        self.sort_with(&options).0
+    }
+    /// Sort according to the options, and also return where every block
+    /// of the focus went.
+    ///
+    /// When the options don't specify an analyzer, the one of the language
+    /// the list was read with is used, if known.
+    pub fn sort_with(
+        self,
+        options: &SortOptions,
+    ) -> (LocList, Vec<BlockMove>) {
+        let analyzer = options
+            .analyzer
+            .or_else(|| self.lang.map(|lang| lang.analyzer()));
+        let lang = self.lang;
+        let mut start = self.before.len();
         let mut locs = self.before.locs;
-        let mut blocks = self.focus.into_blocks();
-        let spacing = Spacing::recognize(&blocks);
-        blocks.sort();
-        spacing.apply(&mut blocks);
-        for block in blocks {
-            locs.extend(block.locs);
+        let blocks = self.focus.into_blocks();
+        let groups = if options.groups {
+            Group::split(blocks)
+        } else {
+            vec![Group {
+                header: LocList::default(),
+                blocks,
+            }]
+        };
+        let mut moves = Vec::new();
+        for group in groups {
+            start += group.header.len();
+            locs.extend(group.header.locs);
+            let (blocks, group_moves) =
+                sort_blocks(group.blocks, start, options, analyzer);
+            for block in blocks {
+                start += block.len();
+                locs.extend(block.locs);
+            }
+            moves.extend(group_moves);
         }
         locs.extend(self.after.locs);
-        LocList { locs }
+        (LocList { locs, lang }, moves)
+    }
+    /// Sort, and also return the mapping from old line indices to
+    /// new ones, for example to keep the cursor on the same item
+    pub fn sort_with_line_mapping(self) -> (LocList, LineMapping) {
+        let (sorted, moves) = self.sort_with_moves();
+        let mapping = LineMapping::new(sorted.len(), &moves);
+        (sorted, mapping)
+    }
+    /// Sort, keeping the order of the blocks the analyzer of the language
+    /// finds order dependent, then check the result can be trusted, and
+    /// return an error instead of a result which may be broken.
+    ///
+    /// The result is analyzed again with the provided language (which should
+    /// be the one used to read the list) and must have the same lines, with
+    /// the same depths out of the focus, and be complete if the input was.
+    pub fn sort_checked(
+        self,
+        lang: Language,
+    ) -> CsResult<LocList> {
+        let original = self.clone();
+        let options = SortOptions {
+            analyzer: Some(lang.analyzer()),
+            ..Default::default()
+        };
+        let (sorted, _) = self.sort_with(&options);
+        original.check_sorted_with(&sorted, lang, &options)?;
+        Ok(sorted)
+    }
+    /// Sort Rust code, then verify the tokens of the sorted blocks
+    /// are exactly the tokens of the original blocks, and return an
+    /// error rather than a suspicious result.
+    ///
+    /// In addition to the checks of `sort_checked`, this proves that no
+    /// block was merged, split or re-associated with a comment of another
+    /// block.
+    pub fn sort_verified(self) -> CsResult<LocList> {
+        let original = self.clone();
+        let sorted = self.sort_checked(Language::Rust)?;
+        original.verify_sorted(&sorted)?;
+        Ok(sorted)
+    }
+    /// Return the whole list, before, focus and after
+    pub fn unfocus(self) -> LocList {
+        let mut locs = self.before.locs;
+        locs.extend(self.focus.locs);
+        locs.extend(self.after.locs);
+        LocList {
+            locs,
+            lang: self.lang,
+        }
     }
+    /// The range of the focus, if it's not empty
+    pub fn focus_range(&self) -> Option<LineNumberRange> {
+        if self.focus.is_empty() {
+            return None;
+        }
+        let start = self.before.len();
+        Some(LineNumberRange {
+            start: LineNumber::from_index(start),
+            end: LineNumber::from_index(start + self.focus.len() - 1),
+        })
+    }
+    /// The ranges of the blocks of the focus, as they would be sorted,
+    /// with line numbers relative to the whole list
+    pub fn block_ranges(&self) -> Vec<LineNumberRange> {
+        block_ranges(&self.focus.clone().into_blocks(), self.before.len())
+    }
+    /// Verify that the blocks of the focus of the sorted list have exactly
+    /// the same Rust tokens than the original blocks
+    pub fn verify_sorted(
+        &self,
+        sorted: &LocList,
+    ) -> CsResult<()> {
+        let focus_start = self.before.len();
+        let mut original_blocks = block_tokens(self.focus.clone());
+        let reread = LocList::read_str_lenient(&sorted.to_string(), Language::Rust)?;
+        // the spacing normalization may have changed the number of lines
+        let Some(focus_end) = reread.len().checked_sub(self.after.len()) else {
+            return Err(CsError::SortRejected("line count changed".to_string()));
+        };
+        if focus_end < focus_start {
+            return Err(CsError::SortRejected("line count changed".to_string()));
+        }
+        let sorted_focus = LocList {
+            locs: reread.locs[focus_start..focus_end].to_vec(),
+            lang: None,
+        };
+        let mut sorted_blocks = block_tokens(sorted_focus);
+        if original_blocks.len() != sorted_blocks.len() {
+            return Err(CsError::SortRejected(format!(
+                "{} blocks before sort, {} after",
+                original_blocks.len(),
+                sorted_blocks.len(),
+            )));
+        }
+        original_blocks.sort();
+        sorted_blocks.sort();
+        if original_blocks != sorted_blocks {
+            return Err(CsError::SortRejected("blocks changed".to_string()));
+        }
+        Ok(())
+    }
+    /// Check that the sorted list is a safe reordering of this focused list
+    pub fn check_sorted(
+        &self,
+        sorted: &LocList,
+        lang: Language,
+    ) -> CsResult<()> {
+        self.check_sorted_with(sorted, lang, &SortOptions::default())
+    }
+    /// Check that the list sorted with the given options is a safe
+    /// reordering of this focused list
+    pub fn check_sorted_with(
+        &self,
+        sorted: &LocList,
+        lang: Language,
+        options: &SortOptions,
+    ) -> CsResult<()> {
+        let original = self.clone().unfocus();
+        // Blank lines are ignored when the spacing is normalized, as the
+        // normalization may add or remove some
+        let normalized = options.spacing == SpacingMode::Normalize;
+        let is_compared = |loc: &&Loc| !(normalized && loc.is_blank());
+        let mut original_lines: Vec<&str> = original
+            .locs
+            .iter()
+            .filter(is_compared)
+            .map(|loc| loc.content.as_str())
+            .collect();
+        let mut sorted_lines: Vec<&str> = sorted
+            .locs
+            .iter()
+            .filter(is_compared)
+            .map(|loc| loc.content.as_str())
+            .collect();
+        original_lines.sort_unstable();
+        sorted_lines.sort_unstable();
+        if original_lines != sorted_lines {
+            return Err(CsError::SortRejected("lines changed".to_string()));
+        }
+        let reread = LocList::read_str_lenient(&sorted.to_string(), lang)
+            .map_err(|e| CsError::SortRejected(format!("result not readable: {e}")))?;
+        if !normalized && reread.len() != original.len() {
+            return Err(CsError::SortRejected("line count changed".to_string()));
+        }
+        // Every line must be found again at the same depth, which wouldn't
+        // be the case if a block had been moved inside an unclosed one
+        let mut original_lines: Vec<(&str, usize, usize)> = original
+            .locs
+            .iter()
+            .filter(is_compared)
+            .map(|loc| (loc.content.as_str(), loc.start_depth, loc.end_depth))
+            .collect();
+        let mut reread_lines: Vec<(&str, usize, usize)> = reread
+            .locs
+            .iter()
+            .filter(is_compared)
+            .map(|loc| (loc.content.as_str(), loc.start_depth, loc.end_depth))
+            .collect();
+        original_lines.sort_unstable();
+        reread_lines.sort_unstable();
+        if original_lines != reread_lines {
+            return Err(CsError::SortRejected("depths changed".to_string()));
+        }
+        if original.is_complete() && !reread.is_complete() {
+            return Err(CsError::SortRejected("result not complete".to_string()));
+        }
+        let focus_start = self.before.len();
+        let focus_end = reread.len().saturating_sub(self.after.len());
+        if self.focus.is_complete() && focus_end > focus_start {
+            let range = LineNumberRange {
+                start: LineNumber::from_index(focus_start),
+                end: LineNumber::from_index(focus_end - 1),
+            };
+            if !reread.is_range_complete(range) {
+                return Err(CsError::SortRejected("focus not complete".to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sort blocks, the first one starting at the given line index, placing
+/// their comments and restoring their spacing, and return the pieces of
+/// the sorted list with their moves
+fn sort_blocks(
+    blocks: Vec<LocList>,
+    start: LineIndex,
+    options: &SortOptions,
+    analyzer: Option<Analyzer>,
+) -> (Vec<LocList>, Vec<BlockMove>) {
+    let pieces = options.comments.split(blocks);
+    let old_lists: Vec<&LocList> = pieces.iter().map(|piece| &piece.list).collect();
+    let old_ranges = block_ranges(&old_lists, start);
+    // the indices of the pieces holding the code of the blocks
+    let block_pieces: Vec<usize> = (0..pieces.len())
+        .filter(|&i| pieces[i].placement == Placement::Sorted)
+        .collect();
+    let unsorted: Vec<LocList> = block_pieces
+        .iter()
+        .map(|&i| pieces[i].list.clone())
+        .collect();
+    let spacing = match options.spacing {
+        SpacingMode::Keep => Spacing::recognize(&unsorted),
+        SpacingMode::Normalize => Spacing::dominant(&unsorted),
+    };
+    let constraints = analyzer
+        .map(|analyzer| analyzer.order_constraints(&unsorted))
+        .unwrap_or_default();
+    let order = constrained_order(unsorted.len(), &constraints, |a, b| {
+        let (a, b) = (&pieces[block_pieces[a]], &pieces[block_pieces[b]]);
+        a.pin
+            .cmp(&b.pin)
+            .then_with(|| options.comparator.compare(&a.list, &b.list))
+    });
+    let mut sorted: Vec<LocList> = order.iter().map(|&b| unsorted[b].clone()).collect();
+    match options.spacing {
+        SpacingMode::Keep => spacing.apply(&mut sorted),
+        SpacingMode::Normalize => spacing.normalize(&mut sorted),
+    }
+    // Before the code of each block come, in their original order, the
+    // pieces staying at its new index and the pieces moving with it.
+    // Remaining pieces go at the end.
+    let mut new_order = Vec::with_capacity(pieces.len());
+    let mut new_blocks = Vec::with_capacity(pieces.len());
+    for (slot, (&b, block)) in order.iter().zip(sorted).enumerate() {
+        for (i, piece) in pieces.iter().enumerate() {
+            let placement = piece.placement;
+            if placement == Placement::InPlace(slot)
+                || placement == Placement::WithBlock(b)
+            {
+                new_order.push(i);
+                new_blocks.push(piece.list.clone());
+            }
+        }
+        new_order.push(block_pieces[b]);
+        new_blocks.push(block);
+    }
+    for (i, piece) in pieces.iter().enumerate() {
+        let remaining = match piece.placement {
+            Placement::InPlace(slot) => slot >= order.len(),
+            Placement::AtEnd => true,
+            Placement::Sorted | Placement::WithBlock(_) => false,
+        };
+        if remaining {
+            new_order.push(i);
+            new_blocks.push(piece.list.clone());
+        }
+    }
+    let new_ranges = block_ranges(&new_blocks, start);
+    let moves = new_order
+        .iter()
+        .zip(new_blocks.iter().zip(new_ranges))
+        .map(|(&i, (block, new_range))| BlockMove {
+            old_range: old_ranges[i],
+            new_range,
+            sort_key: block.sort_key().unwrap_or_default().to_string(),
+        })
+        .collect();
+    (new_blocks, moves)
+}
+
+/// Sort the indices `0..len` with the comparison function, keeping the
+/// order of the indices of every constraint `(a, b)`, a being before b
+fn constrained_order<F>(
+    len: usize,
+    constraints: &[(usize, usize)],
+    compare: F,
+) -> Vec<usize>
+where
+    F: Fn(usize, usize) -> Ordering,
+{
+    let mut order: Vec<usize> = (0..len).collect();
+    if constraints.is_empty() {
+        order.sort_by(|&a, &b| compare(a, b));
+        return order;
+    }
+    // the smallest index whose predecessors are all placed is placed next
+    let mut placed = vec![false; len];
+    order.clear();
+    while order.len() < len {
+        let next = (0..len)
+            .filter(|&i| !placed[i])
+            .filter(|&i| constraints.iter().all(|&(a, b)| b != i || placed[a]))
+            .min_by(|&a, &b| compare(a, b));
+        let Some(next) = next else {
+            // only possible with a cycle of constraints, which is ignored
+            order.extend((0..len).filter(|&i| !placed[i]));
+            break;
+        };
+        placed[next] = true;
+        order.push(next);
+    }
+    order
+}
+
+/// Return the Rust tokens of every block of the list
+fn block_tokens(list: LocList) -> Vec<Vec<String>> {
+    list.into_blocks()
+        .iter()
+        .map(|block| rust::tokenize(&block.to_string()))
+        .collect()
+}
+
+/// Compute the ranges of consecutive blocks, the first one starting
+/// at the given line index
+fn block_ranges<L: std::borrow::Borrow<LocList>>(
+    blocks: &[L],
+    mut start: LineIndex,
+) -> Vec<LineNumberRange> {
+    blocks
+        .iter()
+        .map(|block| {
+            let len = block.borrow().len();
+            let range = LineNumberRange {
+                start: LineNumber::from_index(start),
+                end: LineNumber::from_index(start + len - 1),
+            };
+            start += len;
+            range
+        })
+        .collect()
 }
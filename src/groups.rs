@@ -0,0 +1,83 @@
+use {
+    crate::*,
+    lazy_regex::regex_is_match,
+};
+
+/// Consecutive blocks, sorted independently of the other groups
+#[derive(Debug, Clone, Default)]
+pub struct Group {
+    /// The lines separating this group from the previous one: blank lines
+    /// and header comments, which don't move
+    pub header: LocList,
+    pub blocks: Vec<LocList>,
+}
+
+impl Group {
+    /// Split blocks into groups, a group starting with a block having
+    /// blank lines at start, or a header comment.
+    ///
+    /// A comment heading a block is a header comment when it's decorated
+    /// (eg `// --- network errors ---`) or followed by a blank line. Other
+    /// comments stay with the block they precede.
+    pub fn split(blocks: Vec<LocList>) -> Vec<Group> {
+        let mut groups: Vec<Group> = Vec::new();
+        for mut block in blocks {
+            let header_len = header_len(&block);
+            if header_len > 0 || groups.is_empty() {
+                let header = LocList {
+                    locs: block.locs.drain(..header_len).collect(),
+                    lang: None,
+                };
+                let blocks = if block.is_empty() {
+                    vec![]
+                } else {
+                    vec![block]
+                };
+                groups.push(Group { header, blocks });
+            } else if let Some(group) = groups.last_mut() {
+                group.blocks.push(block);
+            }
+        }
+        groups
+    }
+}
+
+/// Return the number of lines at the start of the block which separate
+/// it from the previous group
+fn header_len(block: &LocList) -> usize {
+    let locs = &block.locs;
+    let blank_end = block.count_blank_lines_at_start();
+    let comment_end = blank_end
+        + locs[blank_end..]
+            .iter()
+            .take_while(|loc| is_plain_comment(loc))
+            .count();
+    let header_end = comment_end
+        + locs[comment_end..]
+            .iter()
+            .take_while(|loc| loc.is_blank())
+            .count();
+    let comments = &locs[blank_end..comment_end];
+    if !comments.is_empty()
+        && (header_end > comment_end || comments.iter().any(is_decorated_comment))
+    {
+        header_end
+    } else {
+        blank_end
+    }
+}
+
+/// Whether the line is made of a comment which isn't a doc comment
+fn is_plain_comment(loc: &Loc) -> bool {
+    let content = loc.content.trim();
+    if content.starts_with("///") || content.starts_with("//!") {
+        return false;
+    }
+    content.starts_with("//") || (content.starts_with("/*") && content.ends_with("*/"))
+}
+
+fn is_decorated_comment(loc: &Loc) -> bool {
+    let content = loc.content.trim();
+    regex_is_match!(r"^(//|/\*)\s*[-=*#~]{2,}", content)
+        || regex_is_match!(r"[-=*#~]{2,}\s*(\*/)?$", content)
+}
@@ -0,0 +1,83 @@
+use {
+    crate::*,
+    std::str::FromStr,
+};
+
+/// Keywords which, if found in the attributes of an item, prevent
+/// its list from being sorted, because its order probably matters
+pub const DEFAULT_EXCLUDING_KEYWORDS: &[&str] = &["repr", "serde", "PartialOrd", "Ord"];
+
+/// A kind of item whose inner list may be sorted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ItemKind {
+    /// The variants of a Rust enum
+    Enum,
+    /// The members of a Rust impl
+    Impl,
+    /// The arms of a Rust match
+    Match,
+    /// The properties of a JS object literal
+    Object,
+    /// The fields of a Rust struct
+    Struct,
+    /// The members of a Rust trait
+    Trait,
+}
+
+/// An item opening a list, as recognized by the analyzer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    pub kind: ItemKind,
+    /// The tokens of the attributes of the item (eg `#[derive(Debug)]`)
+    pub attribute_tokens: Vec<String>,
+    /// The range of the list of the item, if not empty
+    pub list_range: Option<LineNumberRange>,
+}
+
+/// The items whose lists are to be sorted in a whole file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemSelection {
+    pub kinds: Vec<ItemKind>,
+    /// Items with one of those tokens in their attributes are skipped
+    pub excluding_keywords: Vec<String>,
+}
+
+impl ItemSelection {
+    /// Select the items of the given kinds, with the default exclusions
+    pub fn new(kinds: Vec<ItemKind>) -> Self {
+        Self {
+            kinds,
+            excluding_keywords: DEFAULT_EXCLUDING_KEYWORDS
+                .iter()
+                .map(|k| k.to_string())
+                .collect(),
+        }
+    }
+    /// Tell whether the item, given its kind and the tokens of its
+    /// attributes, is selected
+    pub fn accepts(
+        &self,
+        kind: ItemKind,
+        attribute_tokens: &[String],
+    ) -> bool {
+        self.kinds.contains(&kind)
+            && !attribute_tokens
+                .iter()
+                .any(|token| self.excluding_keywords.contains(token))
+    }
+}
+
+impl FromStr for ItemKind {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "enum" | "enums" => Ok(Self::Enum),
+            "impl" | "impls" => Ok(Self::Impl),
+            "match" | "matches" => Ok(Self::Match),
+            "object" | "objects" => Ok(Self::Object),
+            "struct" | "structs" => Ok(Self::Struct),
+            "trait" | "traits" => Ok(Self::Trait),
+            _ => Err(format!("Unknown item kind: {}", s)),
+        }
+    }
+}
@@ -37,22 +37,48 @@
 
 mod analyzers;
 mod brace_stack;
+mod comments;
+mod comparator;
+mod discriminants;
 mod error;
+mod field_key;
 mod focused;
 mod gifts;
+mod groups;
+mod items;
+mod line_items;
+mod line_mapping;
 mod line_number;
 mod loc;
 mod loc_list;
+mod members;
+mod pin;
+mod sort_options;
+mod sort_report;
 mod spacing;
+mod text_position;
 
 pub use {
     analyzers::*,
     brace_stack::*,
+    comments::*,
+    comparator::*,
+    discriminants::*,
     error::*,
+    field_key::*,
     focused::*,
     gifts::*,
+    groups::*,
+    items::*,
+    line_items::*,
+    line_mapping::*,
     line_number::*,
     loc::*,
     loc_list::*,
+    members::*,
+    pin::*,
+    sort_options::*,
+    sort_report::*,
     spacing::*,
+    text_position::*,
 };
@@ -0,0 +1,322 @@
+use {
+    crate::*,
+    std::num::NonZeroUsize,
+};
+
+/// A token of a line, with its byte range
+#[derive(Debug, Clone, Copy)]
+struct Token<'s> {
+    text: &'s str,
+    start: usize,
+    end: usize,
+}
+
+/// The move of an item sorted in a line, in chars from the start of
+/// the line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemMove {
+    pub old_start: usize,
+    pub new_start: usize,
+    pub len: usize,
+}
+
+impl ItemMove {
+    /// Return the new 1-based column of the char at the given column,
+    /// if it's in the moved item
+    pub fn new_column(
+        &self,
+        column: NonZeroUsize,
+    ) -> Option<NonZeroUsize> {
+        let idx = column.get() - 1;
+        if idx < self.old_start || idx >= self.old_start + self.len {
+            return None;
+        }
+        NonZeroUsize::new(column.get() - self.old_start + self.new_start)
+    }
+}
+
+/// Sort the comma separated items of a part of a line, keeping the
+/// spacing around items in place.
+///
+/// The part is given with 1-based char columns, both ends included.
+/// When there's no column, the part is the content of the first braces of
+/// the line. If the part starts with an opening brace and ends with the
+/// matching closing one, only the inside is sorted.
+///
+/// The line is split into tokens by the analyzer, so that the commas and
+/// braces of literals and comments are ignored.
+///
+/// Return the new line and the moves of the items, or `None` when there's
+/// no list in the part.
+pub fn sort_line_items(
+    line: &str,
+    start_column: Option<NonZeroUsize>,
+    end_column: Option<NonZeroUsize>,
+    analyzer: Analyzer,
+) -> Option<(String, Vec<ItemMove>)> {
+    let tokens = token_spans(line, &analyzer.tokenize(line))?;
+    let byte_index = |column: usize| {
+        line.char_indices()
+            .nth(column)
+            .map_or(line.len(), |(i, _)| i)
+    };
+    let (mut start, mut end) = match (start_column, end_column) {
+        (None, None) => {
+            let open = tokens.iter().position(|t| is_opening(t.text))?;
+            let close = matching_brace(&tokens, open)?;
+            (tokens[open].start, tokens[close].end)
+        }
+        (start, end) => {
+            let start = start.map_or(0, |c| byte_index(c.get() - 1));
+            let end = end.map_or(line.len(), |c| byte_index(c.get()));
+            (start, end)
+        }
+    };
+    if start >= end {
+        return None;
+    }
+    let first = tokens
+        .iter()
+        .position(|t| t.end > start)
+        .unwrap_or(tokens.len());
+    let count = tokens[first..].iter().take_while(|t| t.start < end).count();
+    let mut part = &tokens[first..first + count];
+    if part.first().map_or(false, |t| t.start < start)
+        || part.last().map_or(false, |t| t.end > end)
+    {
+        return None; // the part cuts a token
+    }
+    if let (Some(first), Some(last)) = (part.first(), part.last()) {
+        if first.start == start
+            && last.end == end
+            && is_opening(first.text)
+            && matching_brace(part, 0) == Some(part.len() - 1)
+        {
+            start = first.end;
+            end = last.start;
+            part = &part[1..part.len() - 1];
+        }
+    }
+    let (sorted, byte_moves) = sort_items(&line[start..end], start, part)?;
+    let mut new_line = line[..start].to_string();
+    new_line.push_str(&sorted);
+    new_line.push_str(&line[end..]);
+    // the moves are converted from bytes in the list to chars in the line
+    let column = |line: &str, byte: usize| line[..start + byte].chars().count();
+    let moves = byte_moves
+        .into_iter()
+        .map(|m| ItemMove {
+            old_start: column(line, m.old_start),
+            new_start: column(&new_line, m.new_start),
+            len: line[start + m.old_start..start + m.old_start + m.len]
+                .chars()
+                .count(),
+        })
+        .collect();
+    Some((new_line, moves))
+}
+
+/// Find the byte ranges of the tokens, which must be in the line, in
+/// order, separated by whitespaces
+fn token_spans<'s>(
+    line: &'s str,
+    tokens: &[String],
+) -> Option<Vec<Token<'s>>> {
+    let mut spans = Vec::with_capacity(tokens.len());
+    let mut pos = 0;
+    for token in tokens {
+        let start = pos + line[pos..].find(token.as_str())?;
+        let end = start + token.len();
+        spans.push(Token {
+            text: &line[start..end],
+            start,
+            end,
+        });
+        pos = end;
+    }
+    Some(spans)
+}
+
+fn is_opening(token: &str) -> bool {
+    matches!(token, "(" | "[" | "{")
+}
+
+/// Return the index of the token closing the brace at `open`
+fn matching_brace(
+    tokens: &[Token],
+    open: usize,
+) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token.text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Sort the items of a comma separated list, given with its byte offset
+/// in the line and its tokens, ignoring the commas in deeper braces.
+///
+/// Return the sorted list and the moves of the items, in bytes from the
+/// start of the list.
+fn sort_items(
+    list: &str,
+    offset: usize,
+    tokens: &[Token],
+) -> Option<(String, Vec<ItemMove>)> {
+    let mut items = Vec::new();
+    let mut item_starts = Vec::new();
+    let mut depth = 0;
+    let mut item_start = 0;
+    for token in tokens {
+        match token.text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => {
+                if depth == 0 {
+                    return None; // the part isn't balanced
+                }
+                depth -= 1;
+            }
+            "," if depth == 0 => {
+                items.push(&list[item_start..token.start - offset]);
+                item_starts.push(item_start);
+                item_start = token.end - offset;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return None;
+    }
+    items.push(&list[item_start..]);
+    item_starts.push(item_start);
+    // a trailing comma makes an empty last item, which stays last
+    let trailing = items.last().map_or(false, |item| item.trim().is_empty());
+    let count = items.len() - usize::from(trailing);
+    if count < 2 {
+        return None;
+    }
+    let leading_len = |item: &str| item.len() - item.trim_start().len();
+    let mut order: Vec<usize> = (0..count).collect();
+    order.sort_by_key(|&i| items[i].trim());
+    let mut sorted = String::new();
+    let mut moves = Vec::with_capacity(count);
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            sorted.push(',');
+        }
+        if i < count {
+            // the spacing around items stays in place
+            let content = items[order[i]].trim();
+            let trailing = &item[item.trim_end().len()..];
+            sorted.push_str(&item[..leading_len(item)]);
+            let old_start = item_starts[order[i]] + leading_len(items[order[i]]);
+            moves.push(ItemMove {
+                old_start,
+                new_start: sorted.len(),
+                len: content.len(),
+            });
+            sorted.push_str(content);
+            sorted.push_str(trailing);
+        } else {
+            sorted.push_str(item);
+        }
+    }
+    Some((sorted, moves))
+}
+
+#[test]
+fn test_sort_line_items() {
+    let col = NonZeroUsize::new;
+    assert_eq!(
+        sort_line_items("use std::{io, fs, path::Path};", None, None, Analyzer::Rust)
+            .unwrap()
+            .0,
+        "use std::{fs, io, path::Path};",
+    );
+    assert_eq!(
+        sort_line_items(
+            r#"let v = vec!["b,c", f(d, a), "a"];"#,
+            None,
+            None,
+            Analyzer::Rust
+        )
+        .unwrap()
+        .0,
+        r#"let v = vec!["a", "b,c", f(d, a)];"#,
+    );
+    assert_eq!(
+        sort_line_items("foo(c, b, a, ) + bar(z, y)", None, None, Analyzer::Rust)
+            .unwrap()
+            .0,
+        "foo(a, b, c, ) + bar(z, y)",
+    );
+    // with columns, selecting "z, y" (braces excluded) or "(z, y)"
+    assert_eq!(
+        sort_line_items("foo(c, b) + bar(z, y)", col(17), col(20), Analyzer::Rust)
+            .unwrap()
+            .0,
+        "foo(c, b) + bar(y, z)",
+    );
+    assert_eq!(
+        sort_line_items("foo(c, b) + bar(z, y)", col(16), col(21), Analyzer::Rust)
+            .unwrap()
+            .0,
+        "foo(c, b) + bar(y, z)",
+    );
+    assert_eq!(sort_line_items("foo(a)", None, None, Analyzer::Rust), None);
+    assert_eq!(
+        sort_line_items("foo(c, b) + bar(z, y)", col(17), col(21), Analyzer::Rust),
+        None
+    );
+    assert_eq!(
+        sort_line_items("let a = 3;", None, None, Analyzer::Rust),
+        None
+    );
+    // commas and braces in char literals and comments don't split items
+    assert_eq!(
+        sort_line_items("g(b, ',', a);", None, None, Analyzer::Rust)
+            .unwrap()
+            .0,
+        "g(',', a, b);",
+    );
+    assert_eq!(
+        sort_line_items("g(b, /* x, y) */ a);", None, None, Analyzer::Rust)
+            .unwrap()
+            .0,
+        "g(/* x, y) */ a, b);",
+    );
+    assert_eq!(
+        sort_line_items("f('b,c', 'a');", None, None, Analyzer::Javascript)
+            .unwrap()
+            .0,
+        "f('a', 'b,c');",
+    );
+    // the moves of the items give the new columns of their chars
+    let (_, moves) = sort_line_items("f(bb, a);", None, None, Analyzer::Rust).unwrap();
+    assert_eq!(
+        moves,
+        vec![
+            ItemMove {
+                old_start: 6,
+                new_start: 2,
+                len: 1,
+            },
+            ItemMove {
+                old_start: 2,
+                new_start: 5,
+                len: 2,
+            },
+        ]
+    );
+    assert_eq!(moves[1].new_column(col(4).unwrap()), col(7));
+    assert_eq!(moves[1].new_column(col(5).unwrap()), None);
+}
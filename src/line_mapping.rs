@@ -0,0 +1,180 @@
+use crate::*;
+
+/// Where every line of a list went during a sort
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMapping {
+    /// the new index of every line, by old index
+    new_indices: Vec<LineIndex>,
+}
+
+impl LineMapping {
+    /// Build the mapping of a sort of a list of `line_count` lines (before sort),
+    /// from the block moves returned by `Focused::sort_with_moves`
+    pub fn new(
+        line_count: usize,
+        moves: &[BlockMove],
+    ) -> Self {
+        // Lines out of the blocks (eg after the focus) are shifted by the
+        // change of length of the blocks before them, which happens when
+        // the spacing is normalized
+        let mut new_indices: Vec<LineIndex> = (0..line_count)
+            .map(|old| {
+                moves
+                    .iter()
+                    .filter(|mv| mv.old_range.end.to_index() < old)
+                    .fold(old, |idx, mv| {
+                        (idx + range_len(mv.new_range))
+                            .saturating_sub(range_len(mv.old_range))
+                    })
+            })
+            .collect();
+        // Blocks are moved whole, except the blank lines the spacing restoration
+        // may move from the start of a block to the start of another one
+        let mut lost_lines = Vec::new();
+        let mut gained_lines = Vec::new();
+        for mv in moves {
+            let (old_start, old_end) =
+                (mv.old_range.start.to_index(), mv.old_range.end.to_index());
+            let (new_start, new_end) =
+                (mv.new_range.start.to_index(), mv.new_range.end.to_index());
+            let old_len = old_end + 1 - old_start;
+            let new_len = new_end + 1 - new_start;
+            let kept = old_len.min(new_len);
+            lost_lines.extend(
+                (old_start..old_start + old_len - kept).map(|old| (old, new_start)),
+            );
+            gained_lines.extend(new_start..new_start + new_len - kept);
+            for i in 0..kept {
+                new_indices[old_end - i] = new_end - i;
+            }
+        }
+        lost_lines.sort_unstable();
+        gained_lines.sort_unstable();
+        let mut gained_lines = gained_lines.into_iter();
+        for (old, block_start) in lost_lines {
+            // a removed blank line goes with the start of its block
+            new_indices[old] = gained_lines.next().unwrap_or(block_start);
+        }
+        Self { new_indices }
+    }
+    /// Make the mapping of a list of `line_count` lines from which the
+    /// given ranges, which mustn't overlap, are removed.
+    ///
+    /// Removed lines go to the line following them.
+    pub fn removing(
+        line_count: usize,
+        removed: &[LineNumberRange],
+    ) -> Self {
+        let new_indices = (0..line_count)
+            .map(|old| {
+                let removed_before: usize = removed
+                    .iter()
+                    .map(|range| {
+                        let start = range.start.to_index();
+                        let end = range.end.to_index().min(old.saturating_sub(1));
+                        if start < old { end + 1 - start } else { 0 }
+                    })
+                    .sum();
+                old - removed_before
+            })
+            .collect();
+        Self { new_indices }
+    }
+    /// Make the mapping of a list of `line_count` lines where no line moves
+    pub fn identity(line_count: usize) -> Self {
+        Self {
+            new_indices: (0..line_count).collect(),
+        }
+    }
+    /// Combine this mapping with the one of a following sort
+    pub fn then(
+        &self,
+        next: &LineMapping,
+    ) -> Self {
+        Self {
+            new_indices: self
+                .new_indices
+                .iter()
+                .map(|&i| next.new_index(i))
+                .collect(),
+        }
+    }
+    /// Return the index a line went to (lines out of the list don't move)
+    pub fn new_index(
+        &self,
+        old: LineIndex,
+    ) -> LineIndex {
+        self.new_indices.get(old).copied().unwrap_or(old)
+    }
+    pub fn new_line_number(
+        &self,
+        old: LineNumber,
+    ) -> LineNumber {
+        LineNumber::from_index(self.new_index(old.to_index()))
+    }
+    /// Return the range the lines of an old range went to
+    pub fn new_range(
+        &self,
+        old: LineNumberRange,
+    ) -> LineNumberRange {
+        LineNumberRange {
+            start: self.new_line_number(old.start),
+            end: self.new_line_number(old.end),
+        }
+    }
+    /// Return the index of the old line which went to the given index,
+    /// if any. When several ones did, the last one is returned, as removed
+    /// lines go to the line following them.
+    pub fn old_index(
+        &self,
+        new: LineIndex,
+    ) -> Option<LineIndex> {
+        self.new_indices.iter().rposition(|&i| i == new)
+    }
+    /// Return the range of the old lines which went to the given range,
+    /// the ends without old line (eg added blank lines) being kept
+    pub fn old_range(
+        &self,
+        new: LineNumberRange,
+    ) -> LineNumberRange {
+        let old = |line: LineNumber| {
+            self.old_index(line.to_index())
+                .map_or(line, LineNumber::from_index)
+        };
+        LineNumberRange {
+            start: old(new.start),
+            end: old(new.end),
+        }
+    }
+}
+
+fn range_len(range: LineNumberRange) -> usize {
+    range.end.to_index() + 1 - range.start.to_index()
+}
+
+#[test]
+fn test_line_mapping_with_spacing() {
+    let input = r#"
+        enum Choice {
+            Gamma,
+
+            /// the second letter
+            Beta,
+
+            Alpha,
+        }
+    "#;
+    let list = LocList::read_str(input, Language::Rust).unwrap();
+    let focused = list.focus_around_line_index(2).unwrap();
+    let (sorted, mapping) = focused.sort_with_line_mapping();
+    let old_lines: Vec<&str> = input.lines().collect();
+    let new_lines: Vec<String> = sorted.to_string().lines().map(String::from).collect();
+    for (old, line) in old_lines.iter().enumerate() {
+        assert_eq!(&new_lines[mapping.new_index(old)], line);
+    }
+    let mut new_indices: Vec<LineIndex> =
+        (0..old_lines.len()).map(|i| mapping.new_index(i)).collect();
+    new_indices.sort_unstable();
+    new_indices.dedup();
+    assert_eq!(new_indices.len(), old_lines.len());
+}
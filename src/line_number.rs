@@ -1,5 +1,7 @@
 use {
+    crate::*,
     lazy_regex::regex_captures,
+    serde::Serialize,
     std::{
         num::{
             NonZeroUsize,
@@ -14,13 +16,14 @@ use {
 /// This is used for exchanging with the user, and for most
 /// APIs. Disambiguation is done by using either `LineNumber`
 /// or `LineIndex`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(transparent)]
 pub struct LineNumber {
     pub number: NonZeroUsize,
 }
 
 /// A range of 1-based line numbers, both ends included
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub struct LineNumberRange {
     pub start: LineNumber,
     pub end: LineNumber,
@@ -78,6 +81,39 @@ impl LineNumberRange {
     ) -> bool {
         self.start <= line && line <= self.end
     }
+    /// Tell whether the other range is inside this one
+    pub fn includes(
+        self,
+        other: LineNumberRange,
+    ) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+    pub fn overlaps(
+        self,
+        other: LineNumberRange,
+    ) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+    /// Return the ranges in the order they must be sorted so that each
+    /// one still covers the same lines when its turn comes: last first,
+    /// and nested ranges before the ones containing them.
+    ///
+    /// Duplicates are removed, and ranges overlapping without one being
+    /// inside the other are refused, as sorting one would move lines in
+    /// or out of the other one.
+    pub fn sort_order(ranges: &[LineNumberRange]) -> CsResult<Vec<LineNumberRange>> {
+        let mut ranges = ranges.to_vec();
+        ranges.sort_by(|a, b| b.start.cmp(&a.start).then(a.end.cmp(&b.end)));
+        ranges.dedup();
+        for (i, &a) in ranges.iter().enumerate() {
+            for &b in &ranges[i + 1..] {
+                if a.overlaps(b) && !b.includes(a) {
+                    return Err(CsError::OverlappingRanges(b, a));
+                }
+            }
+        }
+        Ok(ranges)
+    }
 }
 
 pub struct LineNumberRangeIter {
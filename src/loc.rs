@@ -32,6 +32,9 @@ pub struct Loc {
     pub gifts: Vec<Gift>,
     /// Before the first char, is the line normal (not a comment or a multi line literal) ?
     pub starts_normal: bool,
+    /// Where the block starting with this line must stay, as decided
+    /// by the analyzer
+    pub pin: Pin,
 }
 
 impl Loc {
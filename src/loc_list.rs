@@ -8,6 +8,9 @@ use {
     },
 };
 
+/// The comment marking a list to sort, see `LocList::marked_ranges`
+pub const SORT_MARKER: &str = "codesort: sort";
+
 /// A list of Lines of Code
 ///
 /// To sort it, you focus it, which specifies the area to sort, then
@@ -15,6 +18,9 @@ use {
 #[derive(Debug, Clone, Default)]
 pub struct LocList {
     pub locs: Vec<Loc>,
+    /// The language the list was read with, when known, whose analyzer
+    /// tells which blocks must keep their relative order when sorting
+    pub lang: Option<Language>,
 }
 
 impl LocList {
@@ -22,7 +28,9 @@ impl LocList {
         reader: R,
         lang: Language,
     ) -> CsResult<Self> {
-        lang.analyzer().read(reader)
+        let mut list = lang.analyzer().read(reader)?;
+        list.lang = Some(lang);
+        Ok(list)
     }
     pub fn read_str(
         s: &str,
@@ -30,6 +38,34 @@ impl LocList {
     ) -> CsResult<LocList> {
         Self::read(s.as_bytes(), lang)
     }
+    /// Read code which may start inside a block, like a selection
+    /// starting in the middle of a match.
+    ///
+    /// The closing braces which can't be matched don't raise an error
+    /// but lower the floor: the first lines get a depth high enough
+    /// for the unmatched braces to be closed.
+    pub fn read_lenient<R: std::io::BufRead>(
+        mut reader: R,
+        lang: Language,
+    ) -> CsResult<Self> {
+        let mut s = String::new();
+        reader.read_to_string(&mut s)?;
+        let analyzer = lang.analyzer();
+        let mut braces = BraceStack::lenient();
+        let mut list = analyzer.read_with_braces(s.as_bytes(), &mut braces)?;
+        if !braces.unmatched.is_empty() {
+            let mut braces = BraceStack::with_floor(&braces.unmatched);
+            list = analyzer.read_with_braces(s.as_bytes(), &mut braces)?;
+        }
+        list.lang = Some(lang);
+        Ok(list)
+    }
+    pub fn read_str_lenient(
+        s: &str,
+        lang: Language,
+    ) -> CsResult<LocList> {
+        Self::read_lenient(s.as_bytes(), lang)
+    }
     pub fn read_file<P: AsRef<Path>>(
         path: P,
         lang: Language,
@@ -48,19 +84,47 @@ impl LocList {
     pub fn len(&self) -> usize {
         self.locs.len()
     }
+    pub fn is_empty(&self) -> bool {
+        self.locs.is_empty()
+    }
 
     pub fn sort_range(
         &mut self,
         range: LineNumberRange,
     ) -> CsResult<()> {
-        let list = LocList {
-            locs: std::mem::take(&mut self.locs),
-        };
-        let focused = list.focus(range)?;
-        let sorted = focused.sort();
-        self.locs = sorted.locs;
+        let focused = std::mem::take(self).focus(range)?;
+        *self = focused.sort();
+        Ok(())
+    }
+    /// Sort several ranges, all given with the line numbers of the list
+    /// before any sort (see `LineNumberRange::sort_order`)
+    pub fn sort_ranges(
+        &mut self,
+        ranges: &[LineNumberRange],
+    ) -> CsResult<()> {
+        for range in LineNumberRange::sort_order(ranges)? {
+            self.sort_range(range)?;
+        }
         Ok(())
     }
+    /// Sort the range, and the lists nested in its blocks, bottom-up,
+    /// down to `max_depth` levels under the range (no limit when `None`)
+    pub fn sort_range_recursive(
+        &mut self,
+        range: LineNumberRange,
+        max_depth: Option<usize>,
+    ) -> CsResult<()> {
+        let mut ranges = self.nested_ranges(range, max_depth);
+        ranges.push(range);
+        self.sort_ranges(&ranges)
+    }
+    /// Sort all the lists marked with a `codesort: sort` comment,
+    /// return the number of sorted lists
+    pub fn sort_marked(&mut self) -> CsResult<usize> {
+        let ranges = self.marked_ranges();
+        self.sort_ranges(&ranges)?;
+        Ok(ranges.len())
+    }
     pub fn sort_around_line_index(
         &mut self,
         line_index: LineIndex,
@@ -81,6 +145,7 @@ impl LocList {
             before: LocList::default(),
             focus: self.clone(),
             after: LocList::default(),
+            lang: self.lang,
         })
     }
     pub fn focus(
@@ -94,19 +159,30 @@ impl LocList {
         }
         let focus = LocList {
             locs: self.locs.drain(start..=end).collect(),
+            lang: self.lang,
         };
         let before = LocList {
             locs: self.locs.drain(..start).collect(),
+            lang: self.lang,
         };
         let after = LocList {
             locs: self.locs.drain(..).collect(),
+            lang: self.lang,
         };
         Ok(Focused {
             before,
             focus,
             after,
+            lang: self.lang,
         })
     }
+    /// Focus on the blocks at the depth of the first line with
+    /// content, which is the lowest common depth of a partial
+    /// selection read with `read_lenient`
+    pub fn focus_floor(self) -> CsResult<Focused> {
+        let range = self.floor_range()?;
+        self.focus(range)
+    }
     pub fn focus_around_line_index(
         self,
         line_idx: LineIndex,
@@ -181,6 +257,24 @@ impl LocList {
             })
         }
     }
+    /// Check the position is in the list, a column being allowed just
+    /// after the end of its line
+    pub fn check_position(
+        &self,
+        position: TextPosition,
+    ) -> CsResult<()> {
+        let Some(loc) = self.locs.get(position.line.to_index()) else {
+            return Err(CsError::PositionOutOfInput(position));
+        };
+        let line_len = loc.content.trim_end_matches(['\n', '\r']).chars().count();
+        if position
+            .column
+            .map_or(false, |column| column.get() > line_len + 1)
+        {
+            return Err(CsError::PositionOutOfInput(position));
+        }
+        Ok(())
+    }
     pub fn check_range(
         &self,
         range: LineNumberRange,
@@ -212,10 +306,8 @@ impl LocList {
         let mut has_not_annotation = false; // considering only root level locs
         let mut has_sortable = false;
         for loc in &self.locs[range.start.to_index()..=range.end.to_index()] {
-            if loc.start_depth == first_start_depth {
-                if loc.is_sortable() {
-                    has_not_annotation |= !loc.is_annotation;
-                }
+            if loc.start_depth == first_start_depth && loc.is_sortable() {
+                has_not_annotation |= !loc.is_annotation;
             }
             if has_not_annotation {
                 has_sortable |= loc.is_sortable();
@@ -230,6 +322,69 @@ impl LocList {
         self.full_range()
             .map_or(false, |range| self.range_has_content(range))
     }
+    /// The sort key of the first line which isn't an annotation or
+    /// a comment, if any
+    pub fn sort_key(&self) -> Option<&str> {
+        self.locs
+            .iter()
+            .find(|loc| loc.is_sortable())
+            .map(|loc| loc.sort_key.as_str())
+    }
+    /// The tokens of the code of the list, comments and annotations excluded
+    pub fn code_tokens(&self) -> Vec<String> {
+        self.locs
+            .iter()
+            .filter(|loc| loc.is_sortable() && loc.starts_normal)
+            .flat_map(|loc| code_tokens(&loc.content))
+            .collect()
+    }
+    /// The name identifying the block: the first identifier of its first
+    /// sortable line, modifiers excluded and without path (eg `Warn` in
+    /// `Level::Warn => ...`), or the content of a leading string literal
+    pub fn identifier(&self) -> Option<String> {
+        const MODIFIERS: &[&str] = &[
+            "async", "const", "crate", "fn", "in", "let", "mut", "pub", "ref", "self",
+            "static", "super", "type", "unsafe", "var",
+        ];
+        let loc = self.locs.iter().find(|loc| loc.is_sortable())?;
+        let tokens: Vec<String> = code_tokens(&loc.content).collect();
+        let is_name = |token: &str| {
+            token
+                .trim_start_matches('"')
+                .chars()
+                .next()
+                .map_or(false, |c| c.is_alphabetic() || c == '_')
+        };
+        let mut i = tokens
+            .iter()
+            .position(|token| is_name(token) && !MODIFIERS.contains(&token.as_str()))?;
+        while tokens.get(i + 1).map_or(false, |t| t == ":")
+            && tokens.get(i + 2).map_or(false, |t| t == ":")
+            && tokens.get(i + 3).map_or(false, |t| is_name(t))
+        {
+            i += 3;
+        }
+        Some(tokens[i].trim_matches('"').to_string())
+    }
+    /// Where the block must stay when its list is sorted: at start or end
+    /// when it contains a `codesort: pin-first` or `codesort: pin-last`
+    /// comment, or when the analyzer pinned its first sortable line
+    pub fn pin(&self) -> Pin {
+        for loc in &self.locs {
+            let in_comment =
+                |marker| loc.content.contains(marker) && !loc.sort_key.contains(marker);
+            if in_comment(PIN_FIRST_MARKER) {
+                return Pin::First;
+            }
+            if in_comment(PIN_LAST_MARKER) {
+                return Pin::Last;
+            }
+        }
+        self.locs
+            .iter()
+            .find(|loc| loc.is_sortable())
+            .map_or(Pin::Unpinned, |loc| loc.pin)
+    }
     pub fn last_significant_char(&self) -> Option<char> {
         self.locs
             .iter()
@@ -291,7 +446,7 @@ impl LocList {
     ) -> Vec<LineNumberRange> {
         let mut blocks = Vec::new();
         let mut current = LineNumberRange::of_line(range.start);
-        for line_number in range {
+        for line_number in range.into_iter().skip(1) {
             if self.is_range_complete(current) {
                 blocks.push(current);
                 current = LineNumberRange::of_line(line_number);
@@ -331,6 +486,294 @@ impl LocList {
             end: range.end.to_index(),
         })
     }
+    /// Return the ranges of the lists nested in the blocks of the range,
+    /// and of the lists nested in those ones, down to `max_depth` levels
+    /// under the range (no limit when `None`)
+    pub fn nested_ranges(
+        &self,
+        range: LineNumberRange,
+        max_depth: Option<usize>,
+    ) -> Vec<LineNumberRange> {
+        let mut ranges = Vec::new();
+        if max_depth == Some(0) {
+            return ranges;
+        }
+        for block in self.block_ranges_in_range(range) {
+            let (start, end) = (block.start.to_index(), block.end.to_index());
+            let locs = &self.locs[start..=end];
+            let Some(depth) = locs.iter().map(Loc::min_depth).min() else {
+                continue;
+            };
+            let mut idx = start;
+            while idx <= end {
+                if self.locs[idx].min_depth() <= depth {
+                    idx += 1;
+                    continue;
+                }
+                // the nested list is found around the shallowest line of
+                // the deeper lines
+                let mut region_end = idx;
+                while region_end < end && self.locs[region_end + 1].min_depth() > depth {
+                    region_end += 1;
+                }
+                let anchor = (idx..=region_end)
+                    .min_by_key(|&i| self.locs[i].min_depth())
+                    .unwrap_or(idx);
+                if let Ok(nested) = self.range_around_line_index(anchor) {
+                    if block.includes(nested) {
+                        ranges.push(nested);
+                        ranges
+                            .extend(self.nested_ranges(nested, max_depth.map(|d| d - 1)));
+                    }
+                }
+                idx = region_end + 1;
+            }
+        }
+        ranges
+    }
+    /// Return the ranges of the lists of the selected items, found
+    /// with the given analyzer, in the whole list
+    pub fn item_ranges(
+        &self,
+        analyzer: Analyzer,
+        selection: &ItemSelection,
+    ) -> Vec<LineNumberRange> {
+        let mut ranges = Vec::new();
+        if let Some(range) = self.full_range() {
+            self.add_item_ranges(range, analyzer, selection, &mut ranges);
+        }
+        ranges
+    }
+    fn add_item_ranges(
+        &self,
+        range: LineNumberRange,
+        analyzer: Analyzer,
+        selection: &ItemSelection,
+        ranges: &mut Vec<LineNumberRange>,
+    ) {
+        for block in self.block_ranges_in_range(range) {
+            ranges.extend(self.item_list_range(block, analyzer, selection));
+        }
+        for nested in self.nested_ranges(range, Some(1)) {
+            self.add_item_ranges(nested, analyzer, selection, ranges);
+        }
+    }
+    /// If the block is a selected item, return the range of its list
+    fn item_list_range(
+        &self,
+        block: LineNumberRange,
+        analyzer: Analyzer,
+        selection: &ItemSelection,
+    ) -> Option<LineNumberRange> {
+        let item = self.item_of_block(block, analyzer)?;
+        if !selection.accepts(item.kind, &item.attribute_tokens) {
+            return None;
+        }
+        item.list_range
+    }
+    /// Recognize the item the block is made of, if any
+    pub fn item_of_block(
+        &self,
+        block: LineNumberRange,
+        analyzer: Analyzer,
+    ) -> Option<Item> {
+        let (start, end) = (block.start.to_index(), block.end.to_index());
+        let depth = self.locs[start..=end].iter().map(Loc::min_depth).min()?;
+        let mut attribute_tokens = Vec::new();
+        let mut header_tokens = Vec::new();
+        let mut idx = start;
+        let opener = loop {
+            if idx > end {
+                return None;
+            }
+            let loc = &self.locs[idx];
+            idx += 1;
+            if loc.is_annotation {
+                // the attribute may span several lines
+                attribute_tokens.extend(rust::tokenize(&loc.content));
+                while idx <= end && self.locs[idx - 1].end_depth > depth {
+                    attribute_tokens.extend(rust::tokenize(&self.locs[idx].content));
+                    idx += 1;
+                }
+                continue;
+            }
+            if !loc.is_sortable() || !loc.starts_normal {
+                continue;
+            }
+            header_tokens.extend(code_tokens(&loc.content));
+            if loc.end_depth > depth {
+                break loc;
+            }
+        };
+        if opener.start_depth != depth || opener.end_depth != depth + 1 {
+            return None;
+        }
+        let kind = analyzer.item_kind(&header_tokens)?;
+        let list_range = if idx > end || self.locs[idx].min_depth() <= depth {
+            None // empty list
+        } else {
+            self.range_around_line_index(idx).ok()
+        };
+        Some(Item {
+            kind,
+            attribute_tokens,
+            list_range,
+        })
+    }
+    /// Recognize the item whose list contains the range, if any
+    pub fn container_item(
+        &self,
+        range: LineNumberRange,
+        analyzer: Analyzer,
+    ) -> Option<Item> {
+        let block = self.container_block(range)?;
+        self.item_of_block(block, analyzer)
+    }
+    /// Return the range of the block whose list contains the range, if any
+    pub fn container_block(
+        &self,
+        range: LineNumberRange,
+    ) -> Option<LineNumberRange> {
+        let (start, end) = (range.start.to_index(), range.end.to_index());
+        let depth = self
+            .locs
+            .get(start..=end)?
+            .iter()
+            .map(Loc::min_depth)
+            .min()?;
+        let opener = (0..start)
+            .rev()
+            .find(|&i| self.locs[i].min_depth() < depth)?;
+        self.block_range_of_line_number(LineNumber::from_index(opener))
+            .ok()
+    }
+    /// Write the implicit discriminants of the variants of the C-like enum
+    /// whose list is the range, so that sorting the variants doesn't change
+    /// their values, and return the number of changed variants.
+    ///
+    /// Nothing is changed when the range isn't the list of a C-like enum.
+    pub fn make_discriminants_explicit(
+        &mut self,
+        range: LineNumberRange,
+        lang: Language,
+    ) -> CsResult<usize> {
+        let is_enum = self
+            .container_item(range, lang.analyzer())
+            .map_or(false, |item| item.kind == ItemKind::Enum);
+        if !is_enum {
+            return Ok(0);
+        }
+        let mut discriminants = Discriminants::default();
+        let mut changes = Vec::new();
+        for block in self.block_ranges_in_range(range) {
+            let Some(idx) = block.into_iter().map(|ln| ln.to_index()).find(|&idx| {
+                self.locs[idx].is_sortable() && self.locs[idx].starts_normal
+            }) else {
+                continue;
+            };
+            let content = &self.locs[idx].content;
+            let Some(new_content) = discriminants.make_explicit(content) else {
+                return Ok(0); // not a unit variant
+            };
+            if &new_content != content {
+                changes.push((idx, new_content));
+            }
+        }
+        if changes.is_empty() {
+            return Ok(0);
+        }
+        let count = changes.len();
+        for (idx, content) in changes {
+            self.locs[idx].content = content;
+        }
+        *self = LocList::read_str_lenient(&self.to_string(), lang)?;
+        Ok(count)
+    }
+    /// Return an error if sorting the range could change the behavior
+    /// of the program, for example because the variants of its enum, or
+    /// the fields of a variant, are compared in order of declaration
+    pub fn check_order_independent(
+        &self,
+        range: LineNumberRange,
+        analyzer: Analyzer,
+    ) -> CsResult<()> {
+        let Some(block) = self.container_block(range) else {
+            return Ok(());
+        };
+        let Some(item) = self.item_of_block(block, analyzer) else {
+            // the range may be the fields of a variant, the item being
+            // the enum containing the variant
+            let reason = self
+                .container_item(block, analyzer)
+                .and_then(|item| analyzer.variant_fields_order_dependency(&item));
+            return match reason {
+                Some(reason) => Err(CsError::OrderDependent(reason.to_string())),
+                None => Ok(()),
+            };
+        };
+        let blocks: Vec<Vec<String>> = self
+            .block_ranges_in_range(range)
+            .into_iter()
+            .map(|block| {
+                block
+                    .into_iter()
+                    .map(|ln| &self.locs[ln.to_index()])
+                    .filter(|loc| loc.is_sortable() && loc.starts_normal)
+                    .flat_map(|loc| code_tokens(&loc.content))
+                    .collect()
+            })
+            .collect();
+        match analyzer.order_dependency(&item, &blocks) {
+            Some(reason) => Err(CsError::OrderDependent(reason.to_string())),
+            None => Ok(()),
+        }
+    }
+    /// Find the blocks of the range having the same code and annotations
+    /// as a previous block of the range
+    pub fn duplicates(
+        &self,
+        range: LineNumberRange,
+    ) -> Vec<Duplicate> {
+        let comparator =
+            BlockComparator::new(vec![Criterion::SortKey, Criterion::Annotations]);
+        let blocks: Vec<(LineNumberRange, LocList)> = self
+            .block_ranges_in_range(range)
+            .into_iter()
+            .map(|range| {
+                let locs =
+                    self.locs[range.start.to_index()..=range.end.to_index()].to_vec();
+                (range, LocList { locs, lang: None })
+            })
+            .filter(|(_, block)| block.sort_key().is_some())
+            .collect();
+        let mut duplicates = Vec::new();
+        for (i, (range, block)) in blocks.iter().enumerate() {
+            let original = blocks[..i].iter().find(|(_, previous)| {
+                comparator.compare(previous, block) == Ordering::Equal
+            });
+            if let Some((original, _)) = original {
+                duplicates.push(Duplicate {
+                    original: *original,
+                    duplicate: *range,
+                });
+            }
+        }
+        duplicates
+    }
+    /// Remove the blocks of the range having the same code and annotations
+    /// as a previous block of the range, and return them
+    pub fn remove_duplicates(
+        &mut self,
+        range: LineNumberRange,
+    ) -> Vec<Duplicate> {
+        let duplicates = self.duplicates(range);
+        for duplicate in duplicates.iter().rev() {
+            let range = duplicate.duplicate;
+            self.locs
+                .drain(range.start.to_index()..=range.end.to_index());
+        }
+        duplicates
+    }
     pub fn into_blocks(self) -> Vec<LocList> {
         let mut blocs = Vec::new();
         let mut current = LocList::default();
@@ -345,6 +788,139 @@ impl LocList {
         }
         blocs
     }
+    /// Return the range of the lines at the depth of the first line with
+    /// content, or deeper, leading blank lines excluded
+    pub fn floor_range(&self) -> CsResult<LineNumberRange> {
+        let Some(line_idx) = self.locs.iter().position(|loc| loc.is_sortable()) else {
+            return Err(CsError::NoSortableRangeAround(0));
+        };
+        let mut range = self.range_around_line_index(line_idx)?;
+        while range.start.to_index() < line_idx
+            && self.locs[range.start.to_index()].is_blank()
+        {
+            range.start = LineNumber::from_index(range.start.to_index() + 1);
+        }
+        Ok(range)
+    }
+    /// Snap a selection, which may start or end in the middle of lines,
+    /// to the blocks it touches.
+    ///
+    /// The blocks are the ones of the list containing the shallowest line of
+    /// the selection. A selection ending at the very start of a line doesn't
+    /// include that line.
+    pub fn snap_range(
+        &self,
+        range: TextRange,
+    ) -> CsResult<LineNumberRange> {
+        let mut lines = range.line_range();
+        if lines.end > lines.start && range.end.column.map_or(false, |c| c.get() == 1) {
+            lines.end = LineNumber::from_index(lines.end.to_index() - 1);
+        }
+        self.check_range(lines)?;
+        let shallowest = lines
+            .into_iter()
+            .filter(|&ln| self.locs[ln.to_index()].is_sortable())
+            .min_by_key(|&ln| self.locs[ln.to_index()].min_depth())
+            .unwrap_or(lines.start);
+        let list_range = self.range_around_line_number(shallowest)?;
+        let blocks: Vec<LineNumberRange> = self
+            .block_ranges_in_range(list_range)
+            .into_iter()
+            .filter(|block| block.start <= lines.end && lines.start <= block.end)
+            .collect();
+        match (blocks.first(), blocks.last()) {
+            (Some(first), Some(last)) => Ok(LineNumberRange {
+                start: first.start,
+                end: last.end,
+            }),
+            _ => Err(CsError::NoSortableRangeAround(shallowest.to_index())),
+        }
+    }
+    /// Sort the comma separated items of a part of a line, for example
+    /// the `b, a` of `use {b, a};`, and return the new list with the
+    /// moves of the items in the line.
+    ///
+    /// When the range has no column, the sorted part is the content of
+    /// the first braces of the line.
+    pub fn sort_in_line(
+        &self,
+        range: TextRange,
+        lang: Language,
+    ) -> CsResult<(LocList, Vec<ItemMove>)> {
+        let idx = range.start.line.to_index();
+        let Some(loc) = self.locs.get(idx) else {
+            return Err(CsError::InvalidRange {
+                start: idx,
+                end: idx,
+            });
+        };
+        let line = loc.content.trim_end_matches(['\n', '\r']);
+        let end_column = if range.is_single_line() {
+            range.end.column
+        } else {
+            None
+        };
+        let analyzer = lang.analyzer();
+        let Some((new_line, moves)) =
+            sort_line_items(line, range.start.column, end_column, analyzer)
+        else {
+            return Err(CsError::RangeNotSortable(lang));
+        };
+        // the sort must only have moved tokens, not cut or merged any
+        let mut tokens = analyzer.tokenize(line);
+        let mut new_tokens = analyzer.tokenize(&new_line);
+        tokens.sort_unstable();
+        new_tokens.sort_unstable();
+        if tokens != new_tokens {
+            return Err(CsError::SortRejected("tokens changed".to_string()));
+        }
+        let mut text = String::new();
+        for (i, l) in self.locs.iter().enumerate() {
+            if i == idx {
+                text.push_str(&new_line);
+                text.push_str(&loc.content[line.len()..]);
+            } else {
+                text.push_str(&l.content);
+            }
+        }
+        let sorted = LocList::read_str_lenient(&text, lang)?;
+        let new_loc = &sorted.locs[idx];
+        if new_loc.start_depth != loc.start_depth || new_loc.end_depth != loc.end_depth {
+            return Err(CsError::SortRejected("depths changed".to_string()));
+        }
+        Ok((sorted, moves))
+    }
+    /// Return the ranges of the lists marked for sorting with a
+    /// `codesort: sort` comment, either on the line opening the list
+    /// or on the line before
+    pub fn marked_ranges(&self) -> Vec<LineNumberRange> {
+        let mut ranges: Vec<LineNumberRange> = Vec::new();
+        for (idx, loc) in self.locs.iter().enumerate() {
+            if !loc.content.contains(SORT_MARKER) || loc.sort_key.contains(SORT_MARKER) {
+                continue; // no marker, or not in a comment
+            }
+            let opens_list = |i: usize| {
+                self.locs
+                    .get(i)
+                    .map_or(false, |l| l.end_depth > l.start_depth)
+            };
+            let Some(opening_idx) = [idx, idx + 1].into_iter().find(|&i| opens_list(i))
+            else {
+                continue;
+            };
+            let list_idx = opening_idx + 1;
+            if list_idx >= self.locs.len() {
+                continue;
+            }
+            let Ok(range) = self.range_around_line_index(list_idx) else {
+                continue;
+            };
+            if !ranges.contains(&range) {
+                ranges.push(range);
+            }
+        }
+        ranges
+    }
     pub fn range_around_line_number(
         &self,
         line_number: LineNumber,
@@ -381,6 +957,13 @@ impl LocList {
     }
 }
 
+/// The tokens of a line, comments excluded
+fn code_tokens(content: &str) -> impl Iterator<Item = String> {
+    rust::tokenize(content)
+        .into_iter()
+        .filter(|token| !token.starts_with("//") && !token.starts_with("/*"))
+}
+
 impl fmt::Display for LocList {
     fn fmt(
         &self,
@@ -1,5 +1,28 @@
 mod cli;
 
-fn main() -> codesort::CsResult<()> {
-    cli::run()
+use {
+    codesort::CsError,
+    std::process::ExitCode,
+};
+
+/// Exit code when the sort was done but its result rejected, the
+/// input being written back unchanged
+const EXIT_SORT_REJECTED: u8 = 2;
+
+fn main() -> ExitCode {
+    match cli::run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e @ CsError::SortRejected(_)) => {
+            eprintln!("{e} - input left unchanged");
+            ExitCode::from(EXIT_SORT_REJECTED)
+        }
+        Err(e @ CsError::OrderDependent(_)) => {
+            eprintln!("{e} - use --force to sort anyway");
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,206 @@
+use {
+    crate::*,
+    std::{
+        cmp::Ordering,
+        fmt,
+        str::FromStr,
+    },
+};
+
+/// The category of a member of a Rust `impl` or `trait`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemberCategory {
+    /// An associated type
+    Type,
+    /// An associated constant
+    Const,
+    /// A function named `new` or `with_*`, whatever its visibility
+    Constructor,
+    /// A `pub` function
+    Pub,
+    /// A function whose visibility is restricted, like `pub(crate)`
+    PubCrate,
+    /// A function without visibility, eg a private helper or a trait method
+    Private,
+}
+
+/// A member of a Rust `impl` or `trait`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub category: MemberCategory,
+    pub name: String,
+}
+
+impl MemberCategory {
+    /// The conventional order: associated types and constants, then
+    /// constructors, then functions from the most visible to the private ones
+    pub fn default_order() -> Vec<Self> {
+        vec![
+            Self::Type,
+            Self::Const,
+            Self::Constructor,
+            Self::Pub,
+            Self::PubCrate,
+            Self::Private,
+        ]
+    }
+}
+
+impl FromStr for MemberCategory {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "type" => Ok(Self::Type),
+            "const" => Ok(Self::Const),
+            "constructor" => Ok(Self::Constructor),
+            "pub" => Ok(Self::Pub),
+            "pub-crate" => Ok(Self::PubCrate),
+            "private" => Ok(Self::Private),
+            _ => Err(format!("Unknown member category: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for MemberCategory {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            Self::Type => "type",
+            Self::Const => "const",
+            Self::Constructor => "constructor",
+            Self::Pub => "pub",
+            Self::PubCrate => "pub-crate",
+            Self::Private => "private",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Member {
+    /// Recognize the member declared by a block, attributes and comments
+    /// being ignored
+    pub fn from_block(block: &LocList) -> Option<Self> {
+        let tokens = block.code_tokens();
+        let mut i = 0;
+        let mut visibility = MemberCategory::Private;
+        if tokens.first().map_or(false, |t| t == "pub") {
+            i += 1;
+            visibility = MemberCategory::Pub;
+            if tokens.get(i).map_or(false, |t| t == "(") {
+                i += tokens[i..].iter().position(|t| t == ")")? + 1;
+                visibility = MemberCategory::PubCrate;
+            }
+        }
+        loop {
+            let token = tokens.get(i)?.as_str();
+            let name = || {
+                tokens
+                    .get(i + 1)
+                    .filter(|t| t.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+                    .cloned()
+            };
+            match token {
+                "fn" => {
+                    let name = name()?;
+                    let category = if name == "new" || name.starts_with("with_") {
+                        MemberCategory::Constructor
+                    } else {
+                        visibility
+                    };
+                    return Some(Self { category, name });
+                }
+                "type" => {
+                    return Some(Self {
+                        category: MemberCategory::Type,
+                        name: name()?,
+                    });
+                }
+                "const"
+                    if tokens
+                        .get(i + 1)
+                        .map_or(false, |t| t != "fn" && t != "unsafe") =>
+                {
+                    return Some(Self {
+                        category: MemberCategory::Const,
+                        name: name()?,
+                    });
+                }
+                "async" | "const" | "default" | "extern" | "unsafe" => {
+                    i += 1;
+                }
+                _ if token.starts_with('"') => {
+                    i += 1; // the ABI of an extern fn
+                }
+                _ => {
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Compare the blocks on the rank of the category of the members they
+/// declare in the given order, then on the names of the members.
+///
+/// Blocks which aren't members, or whose category isn't in the order, come
+/// after the other ones.
+pub fn compare_members(
+    order: &[MemberCategory],
+    a: &LocList,
+    b: &LocList,
+) -> Ordering {
+    let key = |block: &LocList| {
+        Member::from_block(block).map(|member| {
+            let rank = order
+                .iter()
+                .position(|&category| category == member.category);
+            (rank.unwrap_or(order.len()), member.name)
+        })
+    };
+    match (key(a), key(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[test]
+fn test_member_categories() {
+    let category = |code: &str| {
+        let block = LocList::read_str(code, Language::Rust).unwrap();
+        Member::from_block(&block).map(|member| member.category)
+    };
+    assert_eq!(
+        category("pub fn new() -> Self {}"),
+        Some(MemberCategory::Constructor)
+    );
+    assert_eq!(
+        category("fn with_capacity(n: usize) -> Self {}"),
+        Some(MemberCategory::Constructor)
+    );
+    assert_eq!(
+        category("/// doc\n#[inline]\npub const fn len(&self) -> usize {}"),
+        Some(MemberCategory::Pub)
+    );
+    assert_eq!(
+        category("pub(crate) async fn load(&self) {}"),
+        Some(MemberCategory::PubCrate)
+    );
+    assert_eq!(
+        category("unsafe extern \"C\" fn raw() {}"),
+        Some(MemberCategory::Private)
+    );
+    assert_eq!(
+        category("fn name(&self) -> &str;"),
+        Some(MemberCategory::Private)
+    );
+    assert_eq!(category("type Item = u8;"), Some(MemberCategory::Type));
+    assert_eq!(
+        category("pub const MAX: usize = 3;"),
+        Some(MemberCategory::Const)
+    );
+    assert_eq!(category("my_macro!();"), None);
+}
@@ -0,0 +1,63 @@
+use crate::*;
+
+/// A comment pinning the block containing it at the start of its list
+pub const PIN_FIRST_MARKER: &str = "codesort: pin-first";
+
+/// A comment pinning the block containing it at the end of its list
+pub const PIN_LAST_MARKER: &str = "codesort: pin-last";
+
+/// Where a block stays when its list is sorted, whatever its sort key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Pin {
+    First,
+    #[default]
+    Unpinned,
+    Last,
+}
+
+impl Pin {
+    /// Return the pin of a Rust line starting out of any comment or
+    /// literal: catch-all match arms (`_ =>` or a bare binding, without
+    /// guard) and the `..base` or `..` rest of a struct stay at the end
+    pub fn of_rust_line(line: &str) -> Self {
+        let tokens: Vec<String> = rust::tokenize(line)
+            .into_iter()
+            .filter(|t| !t.starts_with("//") && !t.starts_with("/*"))
+            .collect();
+        let tokens: Vec<&str> = tokens.iter().map(String::as_str).collect();
+        match tokens.as_slice() {
+            // a range pattern, like `..=9 =>`, isn't a rest
+            [".", ".", rest @ ..] => {
+                let is_range = rest.first() == Some(&"=")
+                    || rest.windows(2).any(|w| w == ["=", ">"]);
+                if is_range { Self::Unpinned } else { Self::Last }
+            }
+            [name, "=", ">", ..] if is_catch_all(name) => Self::Last,
+            _ => Self::Unpinned,
+        }
+    }
+}
+
+/// Tell whether the pattern, made of a single token, matches anything
+fn is_catch_all(token: &str) -> bool {
+    token != "true"
+        && token != "false"
+        && token.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && token.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[test]
+fn test_rust_pins() {
+    assert_eq!(Pin::of_rust_line("_ => {}"), Pin::Last);
+    assert_eq!(Pin::of_rust_line("other => \"other\","), Pin::Last);
+    assert_eq!(Pin::of_rust_line("_ => 0, // anything else"), Pin::Last);
+    assert_eq!(Pin::of_rust_line("..Default::default()"), Pin::Last);
+    assert_eq!(Pin::of_rust_line(".."), Pin::Last);
+    assert_eq!(Pin::of_rust_line("x if x > 3 => 0,"), Pin::Unpinned);
+    assert_eq!(Pin::of_rust_line("n if ready => 1,"), Pin::Unpinned);
+    assert_eq!(Pin::of_rust_line("..=9 => \"digit\","), Pin::Unpinned);
+    assert_eq!(Pin::of_rust_line("..9 => \"digit\","), Pin::Unpinned);
+    assert_eq!(Pin::of_rust_line("None => 0,"), Pin::Unpinned);
+    assert_eq!(Pin::of_rust_line("true => 1,"), Pin::Unpinned);
+    assert_eq!(Pin::of_rust_line("Some(x) => x,"), Pin::Unpinned);
+}
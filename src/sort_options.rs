@@ -0,0 +1,17 @@
+use crate::*;
+
+/// Options changing the way the blocks of a focus are sorted
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SortOptions {
+    /// Sort separately the groups of blocks separated by blank lines or
+    /// header comments, keeping the separators in place
+    pub groups: bool,
+    pub spacing: SpacingMode,
+    /// Where the comments around the blocks go
+    pub comments: CommentAttachment,
+    /// How the blocks are compared
+    pub comparator: BlockComparator,
+    /// The analyzer of the code, when known, to find the blocks whose
+    /// relative order must be kept (eg overlapping match arms in Rust)
+    pub analyzer: Option<Analyzer>,
+}
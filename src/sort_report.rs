@@ -0,0 +1,184 @@
+use {
+    crate::*,
+    serde::Serialize,
+    std::fmt,
+};
+
+/// Where a block of the focus went during sort
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlockMove {
+    pub old_range: LineNumberRange,
+    pub new_range: LineNumberRange,
+    pub sort_key: String,
+}
+
+/// A block of a list with the same code and annotations as a previous
+/// block of the list, comments and spaces being ignored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Duplicate {
+    /// The first block with this content
+    pub original: LineNumberRange,
+    pub duplicate: LineNumberRange,
+}
+
+/// A replacement of whole lines, both ends included
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TextEdit {
+    pub start: LineNumber,
+    pub end: LineNumber,
+    /// The new lines, each one with its ending newline
+    pub replacement: String,
+}
+
+impl TextEdit {
+    /// Compute the edits changing a list into another one.
+    ///
+    /// When both lists have the same number of lines, consecutive changed
+    /// lines are grouped. Otherwise, a single edit covers all lines between
+    /// the common start and the common end.
+    pub fn between(
+        old: &LocList,
+        new: &LocList,
+    ) -> Vec<TextEdit> {
+        if old.len() != new.len() {
+            return Self::replacing_middle(old, new).into_iter().collect();
+        }
+        let mut edits = Vec::new();
+        let mut current: Option<TextEdit> = None;
+        for (idx, (old_loc, new_loc)) in old.locs.iter().zip(new.locs.iter()).enumerate()
+        {
+            if old_loc.content == new_loc.content {
+                edits.extend(current.take());
+                continue;
+            }
+            let line_number = LineNumber::from_index(idx);
+            let edit = current.get_or_insert_with(|| TextEdit {
+                start: line_number,
+                end: line_number,
+                replacement: String::new(),
+            });
+            edit.end = line_number;
+            edit.replacement.push_str(&new_loc.content);
+        }
+        edits.extend(current);
+        edits
+    }
+    /// Make the edit replacing the lines which aren't in both the common
+    /// start and the common end of the lists
+    fn replacing_middle(
+        old: &LocList,
+        new: &LocList,
+    ) -> Option<TextEdit> {
+        let same = |a: &Loc, b: &Loc| a.content == b.content;
+        let max_common = old.len().min(new.len());
+        let prefix = old
+            .locs
+            .iter()
+            .zip(&new.locs)
+            .take_while(|(a, b)| same(a, b))
+            .count();
+        let suffix = old
+            .locs
+            .iter()
+            .rev()
+            .zip(new.locs.iter().rev())
+            .take(max_common - prefix)
+            .take_while(|(a, b)| same(a, b))
+            .count();
+        let mut start = prefix;
+        // the edit must cover at least one old line, as both ends are included
+        if start + suffix == old.len() {
+            start = start.checked_sub(1)?;
+        }
+        let old_end = old.len() - suffix - 1;
+        let new_end = new.len() - suffix - 1;
+        let replacement = new.locs[start..=new_end]
+            .iter()
+            .map(|loc| loc.content.as_str())
+            .collect();
+        Some(TextEdit {
+            start: LineNumber::from_index(start),
+            end: LineNumber::from_index(old_end),
+            replacement,
+        })
+    }
+}
+
+/// A description of one or several sorts, for editor integrations
+/// which don't want to replace the whole text
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SortReport {
+    /// The ranges which were sorted, in the order of the sorts
+    pub ranges: Vec<LineNumberRange>,
+    pub blocks: Vec<BlockMove>,
+    pub warnings: Vec<String>,
+    /// The duplicate blocks found, or removed, before sorting
+    pub duplicates: Vec<Duplicate>,
+    /// The edits which, applied to the original text, give the sorted one
+    pub edits: Vec<TextEdit>,
+    /// The new position of the cursor, when one was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<TextPosition>,
+}
+
+impl SortReport {
+    /// Build the report of a sort, from the focused list before sort,
+    /// the sorted list, and the block moves returned by the sort
+    pub fn new(
+        original: &Focused,
+        sorted: &LocList,
+        blocks: Vec<BlockMove>,
+    ) -> Self {
+        let mut report = Self::default();
+        report.add_sort(original, blocks);
+        report.edits = TextEdit::between(&original.clone().unfocus(), sorted);
+        report
+    }
+    /// Add the range, the block moves and the warnings of a sort.
+    ///
+    /// The edits aren't updated, as they're computed once between the
+    /// original list and the list after all sorts.
+    pub fn add_sort(
+        &mut self,
+        original: &Focused,
+        blocks: Vec<BlockMove>,
+    ) {
+        self.ranges.extend(original.focus_range());
+        if !original.focus.is_complete() {
+            self.warnings
+                .push("The focused range isn't made of complete blocks".to_string());
+        }
+        let focus_start = original.before.len();
+        for block in &blocks {
+            // comments moved according to their attachment policy don't
+            // need a sort key
+            let only_comments = block.old_range.into_iter().all(|line| {
+                original
+                    .focus
+                    .locs
+                    .get(line.to_index() - focus_start)
+                    .map_or(true, |loc| {
+                        loc.is_blank() || (loc.sort_key.is_empty() && !loc.is_annotation)
+                    })
+            });
+            if block.sort_key.is_empty() && !only_comments {
+                self.warnings
+                    .push(format!("Block {} has no sort key", block.old_range));
+            }
+        }
+        self.blocks.extend(blocks);
+    }
+}
+
+impl fmt::Display for Duplicate {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "lines {} duplicate lines {}",
+            self.duplicate, self.original
+        )
+    }
+}
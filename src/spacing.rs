@@ -1,32 +1,102 @@
-use crate::*;
+use {
+    crate::*,
+    std::str::FromStr,
+};
 
 /// The kind of spacing between blocs
-///
-/// We're only interested, for now, in the specific "Between" kind, which
-/// is the only one which is broken by raw sorting.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Spacing {
+    /// no blank line at all
+    Compact,
     /// at least one blank line between every two blocs, and none
     /// before the first one
     Between,
+    /// blank lines before the multi-line blocs only, and none before the
+    /// first one (one-liners being packed)
+    BeforeMultiLine,
     Other,
 }
 
+/// What to do with the blank lines between blocks when sorting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpacingMode {
+    /// Restore the recognized spacing, with the blank lines of the list
+    #[default]
+    Keep,
+    /// Rewrite the blank lines between blocks, one at most, according to
+    /// the recognized spacing, or the dominant one when not consistent.
+    /// This may change the number of lines.
+    Normalize,
+}
+
 impl Spacing {
     pub fn recognize(blocks: &[LocList]) -> Spacing {
-        let mut blocks = blocks.iter();
-        let Some(first) = blocks.next() else {
+        let Some(first) = blocks.first() else {
             return Self::Other;
         };
         if first.count_blank_lines_at_start() > 0 {
             return Self::Other;
         }
-        if blocks.all(|bloc| bloc.count_blank_lines_at_start() > 0) {
+        let others = &blocks[1..];
+        if others
+            .iter()
+            .all(|bloc| bloc.count_blank_lines_at_start() == 0)
+        {
+            Self::Compact
+        } else if others
+            .iter()
+            .all(|bloc| bloc.count_blank_lines_at_start() > 0)
+        {
             Self::Between
+        } else if others
+            .iter()
+            .all(|bloc| (bloc.count_blank_lines_at_start() > 0) == is_multi_line(bloc))
+        {
+            Self::BeforeMultiLine
         } else {
             Self::Other
         }
     }
+    /// Return the recognized spacing or, when not consistent, the one
+    /// followed by most blocks
+    pub fn dominant(blocks: &[LocList]) -> Spacing {
+        let spacing = Self::recognize(blocks);
+        if spacing != Self::Other {
+            return spacing;
+        }
+        let others = blocks.get(1..).unwrap_or_default();
+        let spaced = |blocs: &[&LocList]| {
+            blocs
+                .iter()
+                .filter(|bloc| bloc.count_blank_lines_at_start() > 0)
+                .count()
+        };
+        let all: Vec<&LocList> = others.iter().collect();
+        let multi_line: Vec<&LocList> =
+            others.iter().filter(|bloc| is_multi_line(bloc)).collect();
+        if spaced(&all) * 2 > all.len() {
+            Self::Between
+        } else if spaced(&multi_line) * 2 > multi_line.len() {
+            Self::BeforeMultiLine
+        } else {
+            Self::Compact
+        }
+    }
+    /// Tell whether the block, at this position, should be preceded by
+    /// blank lines
+    fn wants_blank_before(
+        self,
+        idx: usize,
+        block: &LocList,
+    ) -> bool {
+        match self {
+            Self::Between => idx > 0,
+            Self::BeforeMultiLine => idx > 0 && is_multi_line(block),
+            Self::Compact | Self::Other => false,
+        }
+    }
+    /// Restore the spacing after the blocks were reordered, only by moving
+    /// their blank lines, so that the number of lines doesn't change
     pub fn apply(
         self,
         blocks: &mut [LocList],
@@ -56,9 +126,80 @@ impl Spacing {
                     block_without.locs.insert(0, loc);
                 }
             }
-            Self::Other => {}
+            Self::BeforeMultiLine => {
+                // The runs of blank lines are taken from all blocks, then
+                // given back to the blocks wanting them, in order. Runs in
+                // excess go to the blocks following a multi-line one.
+                let mut runs: Vec<Vec<Loc>> = blocks
+                    .iter_mut()
+                    .map(|bloc| {
+                        let count = bloc.count_blank_lines_at_start();
+                        bloc.locs.drain(..count).collect::<Vec<Loc>>()
+                    })
+                    .filter(|run| !run.is_empty())
+                    .collect();
+                runs.reverse();
+                let mut wanting: Vec<usize> = (0..blocks.len())
+                    .filter(|&i| self.wants_blank_before(i, &blocks[i]))
+                    .collect();
+                let following_multi_line: Vec<usize> = (1..blocks.len())
+                    .filter(|&i| !wanting.contains(&i) && is_multi_line(&blocks[i - 1]))
+                    .collect();
+                wanting.extend(following_multi_line);
+                for i in wanting {
+                    let Some(run) = runs.pop() else {
+                        break;
+                    };
+                    blocks[i].locs.splice(0..0, run);
+                }
+                if let Some(last) = blocks.last_mut() {
+                    for run in runs {
+                        last.locs.splice(0..0, run);
+                    }
+                }
+            }
+            Self::Compact | Self::Other => {}
         }
     }
+    /// Rewrite the blank lines between the blocks: one before each block
+    /// wanting some according to the dominant spacing of the blocks before
+    /// sort, none before the other ones
+    pub fn normalize(
+        self,
+        blocks: &mut [LocList],
+    ) {
+        let mut blank_line = None;
+        for bloc in blocks.iter_mut() {
+            let count = bloc.count_blank_lines_at_start();
+            for loc in bloc.locs.drain(..count) {
+                blank_line.get_or_insert(loc);
+            }
+        }
+        let Some(blank_line) = blank_line else {
+            return; // no blank line to write
+        };
+        for (i, bloc) in blocks.iter_mut().enumerate() {
+            if self.wants_blank_before(i, bloc) {
+                bloc.locs.insert(0, blank_line.clone());
+            }
+        }
+    }
+}
+
+impl FromStr for SpacingMode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep" => Ok(Self::Keep),
+            "normalize" => Ok(Self::Normalize),
+            _ => Err(format!("Unknown spacing mode: {}", s)),
+        }
+    }
+}
+
+/// Whether the block has more than one line, blank lines excluded
+fn is_multi_line(block: &LocList) -> bool {
+    block.locs.iter().filter(|loc| !loc.is_blank()).count() > 1
 }
 
 #[test]
@@ -0,0 +1,193 @@
+use {
+    crate::*,
+    lazy_regex::regex_captures,
+    serde::Serialize,
+    std::{
+        fmt,
+        num::NonZeroUsize,
+        str::FromStr,
+    },
+};
+
+/// A position in a text, as given by text editors: a 1-based line
+/// number and an optional 1-based column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct TextPosition {
+    pub line: LineNumber,
+    pub column: Option<NonZeroUsize>,
+}
+
+/// A range of text, as given by text editors, both ends included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextRange {
+    pub start: TextPosition,
+    pub end: TextPosition,
+}
+
+impl TextPosition {
+    /// Return the position of the same char after the lines moved
+    /// according to the mapping
+    pub fn mapped(
+        self,
+        mapping: &LineMapping,
+    ) -> Self {
+        Self {
+            line: mapping.new_line_number(self.line),
+            column: self.column,
+        }
+    }
+    /// Return the position of the same char after the items of a line
+    /// moved, sorted in this line
+    pub fn moved_in_line(
+        self,
+        line: LineNumber,
+        moves: &[ItemMove],
+    ) -> Self {
+        if self.line != line {
+            return self;
+        }
+        let column = self.column.map(|column| {
+            moves
+                .iter()
+                .find_map(|item_move| item_move.new_column(column))
+                .unwrap_or(column)
+        });
+        Self { column, ..self }
+    }
+}
+
+impl TextRange {
+    /// Whether any end has a column, meaning the range comes from
+    /// a selection which may not be made of whole lines
+    pub fn has_columns(&self) -> bool {
+        self.start.column.is_some() || self.end.column.is_some()
+    }
+    pub fn is_single_line(&self) -> bool {
+        self.start.line == self.end.line
+    }
+    /// The range of the lines touched by this range
+    pub fn line_range(&self) -> LineNumberRange {
+        LineNumberRange {
+            start: self.start.line,
+            end: self.end.line,
+        }
+    }
+}
+
+impl From<LineNumberRange> for TextRange {
+    fn from(range: LineNumberRange) -> Self {
+        Self {
+            start: range.start.into(),
+            end: range.end.into(),
+        }
+    }
+}
+
+impl FromStr for TextRange {
+    type Err = String;
+    /// Parse either a range of lines, like `6:26` (for compatibility
+    /// with `LineNumberRange`), `6-26`, or `12`, or a range with
+    /// columns, like `12:5-30:2`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((_, start, end)) =
+            regex_captures!(r"^(\d+(?::\d+)?)-(\d+(?::\d+)?)$", s)
+        {
+            let start: TextPosition = start.parse()?;
+            let end: TextPosition = end.parse()?;
+            if start > end {
+                return Err(format!("Invalid range: {}", s));
+            }
+            return Ok(Self { start, end });
+        }
+        if let Some((_, start, end)) = regex_captures!(r"^(\d+)[^\d]+(\d+)$", s) {
+            let start: LineNumber =
+                start.parse().map_err(|_| format!("Invalid line: {}", s))?;
+            let end: LineNumber =
+                end.parse().map_err(|_| format!("Invalid line: {}", s))?;
+            if start > end {
+                return Err(format!("Invalid range: {}", s));
+            }
+            return Ok(LineNumberRange { start, end }.into());
+        }
+        let line: LineNumber = s.parse().map_err(|_| format!("Invalid range: {}", s))?;
+        Ok(LineNumberRange::of_line(line).into())
+    }
+}
+
+impl fmt::Display for TextRange {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl From<LineNumber> for TextPosition {
+    fn from(line: LineNumber) -> Self {
+        Self { line, column: None }
+    }
+}
+
+impl FromStr for TextPosition {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((_, line, column)) = regex_captures!(r"^(\d+)(?::(\d+))?$", s) else {
+            return Err(format!("Invalid position: {}", s));
+        };
+        let line: LineNumber =
+            line.parse().map_err(|_| format!("Invalid line: {}", s))?;
+        let column = if column.is_empty() {
+            None
+        } else {
+            Some(
+                column
+                    .parse()
+                    .map_err(|_| format!("Invalid column: {}", s))?,
+            )
+        };
+        Ok(Self { line, column })
+    }
+}
+
+impl fmt::Display for TextPosition {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "{}:{}", self.line, column),
+            None => write!(f, "{}", self.line),
+        }
+    }
+}
+
+#[test]
+fn test_parse_text_position() {
+    let pos: TextPosition = "12".parse().unwrap();
+    assert_eq!(pos, TextPosition::from(LineNumber::new(12).unwrap()));
+    let pos: TextPosition = "12:5".parse().unwrap();
+    assert_eq!(pos.column, NonZeroUsize::new(5));
+    assert_eq!(pos.to_string(), "12:5");
+    assert!("0:5".parse::<TextPosition>().is_err());
+    assert!("12:0".parse::<TextPosition>().is_err());
+    assert!("12-5".parse::<TextPosition>().is_err());
+}
+
+#[test]
+fn test_parse_text_range() {
+    let range: TextRange = "6:26".parse().unwrap();
+    assert_eq!(range.line_range().start, LineNumber::new(6).unwrap());
+    assert_eq!(range.line_range().end, LineNumber::new(26).unwrap());
+    assert!(!range.has_columns());
+    let range: TextRange = "12:12".parse().unwrap();
+    assert!(range.is_single_line());
+    let range: TextRange = "12:5-30:2".parse().unwrap();
+    assert!(range.has_columns());
+    assert_eq!(range.to_string(), "12:5-30:2");
+    let range: TextRange = "12-30:2".parse().unwrap();
+    assert_eq!(range.start.column, None);
+    assert_eq!(range.end.column, NonZeroUsize::new(2));
+    assert!("30:1-12:5".parse::<TextRange>().is_err());
+    assert!("26:6".parse::<TextRange>().is_err());
+}
@@ -0,0 +1,108 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+var width = 800;
+ui.resize = function(w){
+    width = w;
+    ui.layout();
+};
+var ratio = 0.75;
+var height = width * ratio;
+ui.layout = function(){
+    canvas.style.height = height + 'px';
+};
+ui.init = function(){
+    ui.layout();
+};
+var canvas = document.getElementById('main');
+"#;
+
+// function assignments move freely, but `height` stays after `width`
+// and `ratio`, and the call stays after the definitions
+static OUTPUT: &str = r#"
+ui.init = function(){
+    ui.layout();
+};
+ui.layout = function(){
+    canvas.style.height = height + 'px';
+};
+ui.resize = function(w){
+    width = w;
+    ui.layout();
+};
+var ratio = 0.75;
+var width = 800;
+var height = width * ratio;
+var canvas = document.getElementById('main');
+"#;
+
+#[test]
+fn test_dependent_statements_keep_their_order() {
+    let list = LocList::read_str(INPUT, Language::Javascript).unwrap();
+    let range = LineNumberRange {
+        start: line_number!(2),
+        end: line_number!(15),
+    };
+    let focused = list.focus(range).unwrap();
+    let blocks = focused.focus.clone().into_blocks();
+    assert_eq!(blocks.len(), 7);
+    assert_eq!(
+        Analyzer::Javascript.order_constraints(&blocks),
+        vec![
+            (0, 3),
+            (2, 3),
+            (0, 6),
+            (1, 6),
+            (2, 6),
+            (3, 6),
+            (4, 6),
+            (5, 6)
+        ],
+    );
+    let sorted = focused.sort_checked(Language::Javascript).unwrap();
+    assert_eq!(sorted.to_string(), OUTPUT);
+}
+
+static CLASS_INPUT: &str = r#"
+class Renderer {
+    zeta() {
+        return this.draw(1);
+    }
+    alpha(x) {
+        this.draw(x);
+    }
+    static create() {
+        return new Renderer();
+    }
+}
+"#;
+
+static CLASS_OUTPUT: &str = r#"
+class Renderer {
+    alpha(x) {
+        this.draw(x);
+    }
+    static create() {
+        return new Renderer();
+    }
+    zeta() {
+        return this.draw(1);
+    }
+}
+"#;
+
+// method definitions don't run their bodies, so they're freely sorted
+#[test]
+fn test_class_methods_are_sorted() {
+    let list = LocList::read_str(CLASS_INPUT, Language::Javascript).unwrap();
+    let range = LineNumberRange {
+        start: line_number!(3),
+        end: line_number!(11),
+    };
+    let focused = list.focus(range).unwrap();
+    let blocks = focused.focus.clone().into_blocks();
+    assert_eq!(blocks.len(), 3);
+    assert!(Analyzer::Javascript.order_constraints(&blocks).is_empty());
+    let sorted = focused.sort_checked(Language::Javascript).unwrap();
+    assert_eq!(sorted.to_string(), CLASS_OUTPUT);
+}
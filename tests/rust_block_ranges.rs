@@ -0,0 +1,64 @@
+use codesort::*;
+
+#[test]
+fn test_block_ranges() {
+    static INPUT: &str = r#"
+    pub enum ContentSearchResult {
+        /// the file wasn't searched because it's binary or too big
+        NotSuitable,
+        /// the needle has been found at the given pos
+        Found {
+            pos: usize,
+        },
+        /// the needle hasn't been found
+        NotFound, // no match
+    }
+    "#;
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_number(line_number!(4)).unwrap();
+    assert_eq!(
+        focused.focus_range(),
+        Some(LineNumberRange {
+            start: line_number!(3),
+            end: line_number!(10),
+        })
+    );
+    let ranges = focused.block_ranges();
+    assert_eq!(
+        ranges,
+        vec![
+            LineNumberRange {
+                start: line_number!(3),
+                end: line_number!(4),
+            },
+            LineNumberRange {
+                start: line_number!(5),
+                end: line_number!(8),
+            },
+            LineNumberRange {
+                start: line_number!(9),
+                end: line_number!(10),
+            },
+        ]
+    );
+    let keys: Vec<String> = focused
+        .focus
+        .into_blocks()
+        .iter()
+        .map(|block| block.sort_key().unwrap().to_string())
+        .collect();
+    assert_eq!(keys, vec!["NotSuitable,", "Found{", "NotFound,"]);
+}
+
+#[test]
+fn test_block_ranges_in_range() {
+    static INPUT: &str = "enum E {\n    B,\n    A,\n    C {\n        x: u8,\n    },\n}\n";
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let range = list.range_around_line_number(line_number!(2)).unwrap();
+    let blocks: Vec<String> = list
+        .block_ranges_in_range(range)
+        .iter()
+        .map(|r| r.to_string())
+        .collect();
+    assert_eq!(blocks, vec!["2:2", "3:3", "4:6"]);
+}
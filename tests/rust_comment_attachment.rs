@@ -0,0 +1,113 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+enum Fruit {
+    /// yellow
+    Banana,
+    /// red
+    Apple,
+
+    // --- the rare ones ---
+
+    /// sweet
+    Avocado,
+    Kiwi,
+    // more to come
+}
+"#;
+
+fn sort(comments: CommentAttachment) -> String {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    // the range includes the trailing comment, which the range around
+    // a line leaves out
+    let range = LineNumberRange {
+        start: line_number!(3),
+        end: line_number!(13),
+    };
+    let focused = list.focus(range).unwrap();
+    let options = SortOptions {
+        comments,
+        ..Default::default()
+    };
+    let (sorted, _) = focused.clone().sort_with(&options);
+    focused
+        .check_sorted_with(&sorted, Language::Rust, &options)
+        .unwrap();
+    sorted.to_string()
+}
+
+#[test]
+fn test_default_attachment() {
+    static OUTPUT: &str = r#"
+enum Fruit {
+    /// red
+    Apple,
+
+    // --- the rare ones ---
+
+    /// sweet
+    Avocado,
+    /// yellow
+    Banana,
+    Kiwi,
+    // more to come
+}
+"#;
+    assert_eq!(sort(CommentAttachment::default()), OUTPUT);
+}
+
+#[test]
+fn test_detached_comments_in_place() {
+    static OUTPUT: &str = r#"
+enum Fruit {
+    /// red
+    Apple,
+    /// sweet
+    Avocado,
+
+    // --- the rare ones ---
+
+    /// yellow
+    Banana,
+    Kiwi,
+    // more to come
+}
+"#;
+    let comments = CommentAttachment {
+        detached: CommentPolicy::InPlace,
+        ..Default::default()
+    };
+    assert_eq!(sort(comments), OUTPUT);
+}
+
+#[test]
+fn test_leading_comments_at_end_and_trailing_with_block() {
+    static OUTPUT: &str = r#"
+enum Fruit {
+    Apple,
+
+    // --- the rare ones ---
+
+    Avocado,
+    Banana,
+    Kiwi,
+    // more to come
+    /// yellow
+    /// red
+    /// sweet
+}
+"#;
+    let comments = CommentAttachment {
+        leading: CommentPolicy::AtEnd,
+        trailing: CommentPolicy::WithBlock,
+        ..Default::default()
+    };
+    assert_eq!(sort(comments), OUTPUT);
+}
+
+#[test]
+fn test_trailing_comment_out_of_range_around() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let range = list.range_around_line_number(line_number!(4)).unwrap();
+    assert_eq!(range.end, line_number!(12));
+}
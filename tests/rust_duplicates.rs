@@ -0,0 +1,66 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+fn color(name: &str) -> Option<Color> {
+    match name {
+        "red" => Some(Color::Red),
+        #[cfg(feature = "extended")]
+        "teal" => Some(Color::Teal),
+        "blue" => Some(Color::Blue),
+        // not reachable
+        "red" => Some(Color::Red),
+        #[cfg(not(feature = "extended"))]
+        "teal" => Some(Color::Teal),
+        _ => None,
+    }
+}
+"#;
+
+static DEDUPED: &str = r#"
+fn color(name: &str) -> Option<Color> {
+    match name {
+        "red" => Some(Color::Red),
+        #[cfg(feature = "extended")]
+        "teal" => Some(Color::Teal),
+        "blue" => Some(Color::Blue),
+        #[cfg(not(feature = "extended"))]
+        "teal" => Some(Color::Teal),
+        _ => None,
+    }
+}
+"#;
+
+#[test]
+fn test_find_duplicates() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let range = list.range_around_line_number(line_number!(4)).unwrap();
+    // blocks with different annotations aren't duplicates, and
+    // comments are ignored
+    assert_eq!(
+        list.duplicates(range),
+        vec![Duplicate {
+            original: LineNumberRange {
+                start: line_number!(4),
+                end: line_number!(4),
+            },
+            duplicate: LineNumberRange {
+                start: line_number!(8),
+                end: line_number!(9),
+            },
+        }]
+    );
+}
+
+#[test]
+fn test_remove_duplicates() {
+    let mut list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let line_count = list.len();
+    let range = list.range_around_line_number(line_number!(4)).unwrap();
+    let duplicates = list.remove_duplicates(range);
+    assert_eq!(list.to_string(), DEDUPED);
+    let removed: Vec<LineNumberRange> = duplicates.iter().map(|d| d.duplicate).collect();
+    let mapping = LineMapping::removing(line_count, &removed);
+    assert_eq!(mapping.new_line_number(line_number!(7)), line_number!(7));
+    assert_eq!(mapping.new_line_number(line_number!(9)), line_number!(8));
+    assert_eq!(mapping.new_line_number(line_number!(11)), line_number!(9));
+}
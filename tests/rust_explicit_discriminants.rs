@@ -0,0 +1,54 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+#[repr(u8)]
+pub enum Code {
+    /// the first
+    Delta = 1,
+    Beta,
+    Charlie, // third
+    Alpha,
+}
+"#;
+
+static OUTPUT: &str = r#"
+#[repr(u8)]
+pub enum Code {
+    Alpha = 4,
+    Beta = 2,
+    Charlie = 3, // third
+    /// the first
+    Delta = 1,
+}
+"#;
+
+#[test]
+fn test_explicit_discriminants() {
+    let mut list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let range = list.range_around_line_number(line_number!(5)).unwrap();
+    assert!(matches!(
+        list.check_order_independent(range, Analyzer::Rust),
+        Err(CsError::OrderDependent(_))
+    ));
+    assert_eq!(
+        list.make_discriminants_explicit(range, Language::Rust)
+            .unwrap(),
+        3
+    );
+    list.check_order_independent(range, Analyzer::Rust).unwrap();
+    list.sort_range(range).unwrap();
+    assert_eq!(list.to_string(), OUTPUT);
+}
+
+#[test]
+fn test_no_explicit_discriminants_for_data_enums() {
+    let input = "enum E {\n    B(u8),\n    A,\n}\n";
+    let mut list = LocList::read_str(input, Language::Rust).unwrap();
+    let range = list.range_around_line_number(line_number!(2)).unwrap();
+    assert_eq!(
+        list.make_discriminants_explicit(range, Language::Rust)
+            .unwrap(),
+        0
+    );
+    assert_eq!(list.to_string(), input);
+}
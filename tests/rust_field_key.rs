@@ -0,0 +1,98 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+pub struct Packet {
+    flags: u8,
+    /// the payload, if any
+    payload: Option<Vec<u8>>,
+    id: u64,
+    #[serde(default)]
+    checksum: u16,
+    kind: u8,
+    timestamp: u64,
+    crc: [u32; 2],
+    valid: bool,
+}
+"#;
+
+static OUTPUT_BY_TYPE: &str = r#"
+pub struct Packet {
+    /// the payload, if any
+    payload: Option<Vec<u8>>,
+    crc: [u32; 2],
+    valid: bool,
+    #[serde(default)]
+    checksum: u16,
+    id: u64,
+    timestamp: u64,
+    flags: u8,
+    kind: u8,
+}
+"#;
+
+static OUTPUT_BY_ALIGNMENT: &str = r#"
+pub struct Packet {
+    id: u64,
+    timestamp: u64,
+    crc: [u32; 2],
+    #[serde(default)]
+    checksum: u16,
+    flags: u8,
+    kind: u8,
+    valid: bool,
+    /// the payload, if any
+    payload: Option<Vec<u8>>,
+}
+"#;
+
+#[test]
+fn test_sort_fields_by_type() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_number(line_number!(3)).unwrap();
+    let comparator =
+        BlockComparator::with_criteria(vec![Criterion::Field(FieldKey::Type)]);
+    assert_eq!(focused.sort_by(comparator).to_string(), OUTPUT_BY_TYPE);
+}
+
+#[test]
+fn test_sort_fields_by_alignment() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_number(line_number!(3)).unwrap();
+    let comparator =
+        BlockComparator::with_criteria(vec![Criterion::Field(FieldKey::Alignment)]);
+    assert_eq!(focused.sort_by(comparator).to_string(), OUTPUT_BY_ALIGNMENT);
+}
+
+#[test]
+fn test_sort_parameters_by_type() {
+    static INPUT: &str = r#"
+impl Canvas {
+    pub fn draw(
+        &mut self,
+        label: &str,
+        y: u32,
+        x: u32,
+        scale: f32,
+    ) {
+    }
+}
+"#;
+    static OUTPUT: &str = r#"
+impl Canvas {
+    pub fn draw(
+        &mut self,
+        label: &str,
+        scale: f32,
+        x: u32,
+        y: u32,
+    ) {
+    }
+}
+"#;
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_number(line_number!(5)).unwrap();
+    let comparator =
+        BlockComparator::with_criteria(vec![Criterion::Field(FieldKey::Type)]);
+    // the receiver stays first
+    assert_eq!(focused.sort_by(comparator).to_string(), OUTPUT);
+}
@@ -0,0 +1,54 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+pub enum ErrorKind {
+    // --- network errors ---
+    Timeout,
+    Refused,
+    /// the host can't be found
+    HostNotFound,
+    // --- io errors ---
+    NotFound,
+    AlreadyExists,
+
+    Other,
+    // a comment of Interrupted
+    Interrupted,
+}
+"#;
+
+static OUTPUT: &str = r#"
+pub enum ErrorKind {
+    // --- network errors ---
+    /// the host can't be found
+    HostNotFound,
+    Refused,
+    Timeout,
+    // --- io errors ---
+    AlreadyExists,
+    NotFound,
+
+    // a comment of Interrupted
+    Interrupted,
+    Other,
+}
+"#;
+
+#[test]
+fn test_sort_in_groups() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_number(line_number!(4)).unwrap();
+    let blocks = focused.focus.clone().into_blocks();
+    let groups = Group::split(blocks);
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups[0].header.len(), 1);
+    assert_eq!(groups[1].blocks.len(), 2);
+    assert_eq!(groups[2].header.len(), 1); // the blank line
+    let options = SortOptions {
+        groups: true,
+        ..Default::default()
+    };
+    let (sorted, moves) = focused.sort_with(&options);
+    assert_eq!(sorted.to_string(), OUTPUT);
+    assert_eq!(moves.len(), 7);
+}
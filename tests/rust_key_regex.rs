@@ -0,0 +1,71 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+fn http_method(code: u8) -> &'static str {
+    match code {
+        0 => "PUT",
+        1 => "GET",
+        2 => "DELETE",
+        // the most common one
+        3 => "POST",
+        _ => "UNKNOWN",
+    }
+}
+
+pub struct Page {
+    title: String,
+    hits: u64,
+    path: PathBuf,
+    /// the last time the page was served
+    served: u64,
+    author: String,
+}
+"#;
+
+static OUTPUT: &str = r#"
+fn http_method(code: u8) -> &'static str {
+    match code {
+        2 => "DELETE",
+        1 => "GET",
+        // the most common one
+        3 => "POST",
+        0 => "PUT",
+        _ => "UNKNOWN",
+    }
+}
+
+pub struct Page {
+    path: PathBuf,
+    author: String,
+    title: String,
+    hits: u64,
+    /// the last time the page was served
+    served: u64,
+}
+"#;
+
+#[test]
+fn test_sort_by_key_regex() {
+    let mut list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let sorts = [
+        (4, r#"=> "(?P<key>[^"]*)""#),
+        (15, r":\s*(?P<key>[\w:<>]+),$"),
+    ];
+    for (line, regex) in sorts {
+        let focused = list
+            .focus_around_line_number(LineNumber::new(line).unwrap())
+            .unwrap();
+        let regex: KeyRegex = regex.parse().unwrap();
+        list = focused.sort_by(BlockComparator::with_key(regex));
+    }
+    assert_eq!(list.to_string(), OUTPUT);
+}
+
+#[test]
+fn test_key_regex_fallback() {
+    // without match, the sort key of the block is used
+    let regex: KeyRegex = "=> (?P<key>None)".parse().unwrap();
+    let block = LocList::read_str("Some(x) => x, // not None", Language::Rust).unwrap();
+    assert_eq!(regex.key(&block), "Some(x)=>x,");
+    assert!("(?P<key>".parse::<KeyRegex>().is_err());
+}
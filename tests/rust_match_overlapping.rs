@@ -0,0 +1,77 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+fn describe(n: Option<u8>) -> &'static str {
+    match n {
+        Some(0) => "zero",
+        Some(n) if n > 100 => "big",
+        None => "none",
+        Some(1) => "one",
+        Some(_) => "other",
+    }
+}
+"#;
+
+// Some(_) would be before Some(n) with a sort ignoring the language,
+// making the guarded arm unreachable
+static UNCONSTRAINED_OUTPUT: &str = r#"
+fn describe(n: Option<u8>) -> &'static str {
+    match n {
+        None => "none",
+        Some(0) => "zero",
+        Some(1) => "one",
+        Some(_) => "other",
+        Some(n) if n > 100 => "big",
+    }
+}
+"#;
+
+static OUTPUT: &str = r#"
+fn describe(n: Option<u8>) -> &'static str {
+    match n {
+        None => "none",
+        Some(0) => "zero",
+        Some(n) if n > 100 => "big",
+        Some(1) => "one",
+        Some(_) => "other",
+    }
+}
+"#;
+
+#[test]
+fn test_overlapping_arms_keep_their_order() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_number(line_number!(4)).unwrap();
+    let blocks = focused.focus.clone().into_blocks();
+    assert_eq!(
+        Analyzer::Rust.order_constraints(&blocks),
+        vec![(0, 1), (1, 3), (0, 4), (1, 4), (3, 4)],
+    );
+    let mut unknown_lang = focused.clone();
+    unknown_lang.lang = None;
+    assert_eq!(unknown_lang.sort().to_string(), UNCONSTRAINED_OUTPUT);
+    assert_eq!(focused.clone().sort().to_string(), OUTPUT);
+    let sorted = focused.sort_checked(Language::Rust).unwrap();
+    assert_eq!(sorted.to_string(), OUTPUT);
+}
+
+#[test]
+fn test_sort_around_line_keeps_overlapping_arms_order() {
+    static INPUT: &str = r#"
+match n {
+    Some(_) => 2,
+    Some(0) => 1,
+    None => 0,
+}
+"#;
+    static OUTPUT: &str = r#"
+match n {
+    None => 0,
+    Some(_) => 2,
+    Some(0) => 1,
+}
+"#;
+    let mut list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    list.sort_around_line_index(2).unwrap();
+    assert_eq!(list.to_string(), OUTPUT);
+}
@@ -89,6 +89,11 @@ fn test_match_struct() {
             block.print_debug(&format!(" BLOCK {i}"));
         }
     }
+    // both patterns end with `..` so they may overlap, and the analyzer of
+    // the language keeps their order
+    assert_eq!(focused.clone().sort().to_string(), INPUT);
+    let mut focused = focused;
+    focused.lang = None;
     let sorted_list = focused.sort();
     sorted_list.print_debug(" SORTED ");
     assert_eq!(sorted_list.to_string(), OUTPUT);
@@ -0,0 +1,101 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+impl Cache {
+    fn evict(&mut self) {
+        self.entries.pop_front();
+    }
+    pub fn get(&self, key: &str) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.key == key)
+    }
+    pub const MAX_ENTRIES: usize = 64;
+    /// Make a cache with room for `capacity` entries
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { entries: VecDeque::with_capacity(capacity) }
+    }
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+    pub fn new() -> Self {
+        Self::with_capacity(Self::MAX_ENTRIES)
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+"#;
+
+static OUTPUT: &str = r#"
+impl Cache {
+    pub const MAX_ENTRIES: usize = 64;
+    pub fn new() -> Self {
+        Self::with_capacity(Self::MAX_ENTRIES)
+    }
+    /// Make a cache with room for `capacity` entries
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { entries: VecDeque::with_capacity(capacity) }
+    }
+    pub fn get(&self, key: &str) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.key == key)
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+    fn evict(&mut self) {
+        self.entries.pop_front();
+    }
+}
+"#;
+
+// private helpers first, the other categories being after, by name
+static OUTPUT_PRIVATE_FIRST: &str = r#"
+impl Cache {
+    fn evict(&mut self) {
+        self.entries.pop_front();
+    }
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+    pub const MAX_ENTRIES: usize = 64;
+    pub fn get(&self, key: &str) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.key == key)
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn new() -> Self {
+        Self::with_capacity(Self::MAX_ENTRIES)
+    }
+    /// Make a cache with room for `capacity` entries
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { entries: VecDeque::with_capacity(capacity) }
+    }
+}
+"#;
+
+#[test]
+fn test_sort_members() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_number(line_number!(3)).unwrap();
+    let order = MemberCategory::default_order();
+    let comparator = BlockComparator::with_criteria(vec![Criterion::Members(order)]);
+    assert_eq!(focused.sort_by(comparator).to_string(), OUTPUT);
+}
+
+#[test]
+fn test_sort_members_in_custom_order() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_number(line_number!(3)).unwrap();
+    let order = vec![MemberCategory::Private, MemberCategory::PubCrate];
+    let comparator = BlockComparator::with_criteria(vec![Criterion::Members(order)]);
+    assert_eq!(
+        focused.sort_by(comparator).to_string(),
+        OUTPUT_PRIVATE_FIRST
+    );
+}
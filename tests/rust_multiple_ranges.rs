@@ -0,0 +1,80 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+fn describe(c: Choice, n: u8) -> &'static str {
+    match c {
+        Choice::Gamma => match n {
+            2 => "two",
+            1 => "one",
+            _ => "many",
+        },
+        Choice::Beta => "beta",
+        Choice::Alpha => match n {
+            9 => "nine",
+            0 => "zero",
+            _ => "other",
+        },
+    }
+}
+"#;
+
+static OUTPUT: &str = r#"
+fn describe(c: Choice, n: u8) -> &'static str {
+    match c {
+        Choice::Alpha => match n {
+            0 => "zero",
+            9 => "nine",
+            _ => "other",
+        },
+        Choice::Beta => "beta",
+        Choice::Gamma => match n {
+            1 => "one",
+            2 => "two",
+            _ => "many",
+        },
+    }
+}
+"#;
+
+#[test]
+fn test_sort_nested_ranges() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    // the line numbers are the ones of the input, whatever the order
+    let ranges = [
+        list.range_around_line_number(line_number!(4)).unwrap(),
+        list.range_around_line_number(line_number!(6)).unwrap(),
+        list.range_around_line_number(line_number!(13)).unwrap(),
+    ];
+    let mut sorted = list.clone();
+    sorted.sort_ranges(&ranges).unwrap();
+    assert_eq!(sorted.to_string(), OUTPUT);
+    let mut sorted = list.clone();
+    sorted
+        .sort_ranges(&[ranges[2], ranges[0], ranges[1], ranges[1]])
+        .unwrap();
+    assert_eq!(sorted.to_string(), OUTPUT);
+}
+
+#[test]
+fn test_sort_order() {
+    let range = |start, end| LineNumberRange {
+        start: LineNumber::new(start).unwrap(),
+        end: LineNumber::new(end).unwrap(),
+    };
+    let order = LineNumberRange::sort_order(&[
+        range(4, 16),
+        range(5, 8),
+        range(12, 15),
+        range(5, 8),
+        range(20, 22),
+    ])
+    .unwrap();
+    assert_eq!(
+        order,
+        vec![range(20, 22), range(12, 15), range(5, 8), range(4, 16)]
+    );
+    assert!(matches!(
+        LineNumberRange::sort_order(&[range(4, 10), range(8, 12)]),
+        Err(CsError::OverlappingRanges(_, _)),
+    ));
+}
@@ -0,0 +1,84 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Level {
+    Low,
+    High,
+}
+#[repr(u8)]
+enum Code {
+    B = 2,
+    A = 1,
+}
+#[repr(u8)]
+enum Implicit {
+    B,
+    A,
+}
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Value {
+    Int(i64),
+    Float(f64),
+}
+#[repr(C)]
+struct Pair {
+    y: u8,
+    x: u8,
+}
+#[repr(transparent)]
+struct Wrapper {
+    b: u8,
+}
+#[derive(PartialEq, PartialOrd)]
+enum Shape {
+    Rect {
+        w: u8,
+        h: u8,
+    },
+}
+enum Plain {
+    Rect {
+        w: u8,
+        h: u8,
+    },
+}
+"#;
+
+#[test]
+fn test_order_dependent_lists() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let check = |line| {
+        let range = list.range_around_line_number(line).unwrap();
+        list.check_order_independent(range, Analyzer::Rust)
+    };
+    assert!(matches!(
+        check(line_number!(4)),
+        Err(CsError::OrderDependent(_))
+    ));
+    assert!(check(line_number!(10)).is_ok()); // explicit discriminants
+    assert!(matches!(
+        check(line_number!(15)),
+        Err(CsError::OrderDependent(_))
+    ));
+    assert!(matches!(
+        check(line_number!(21)),
+        Err(CsError::OrderDependent(_))
+    ));
+    assert!(matches!(
+        check(line_number!(26)),
+        Err(CsError::OrderDependent(_))
+    ));
+    assert!(check(line_number!(31)).is_ok());
+    // the fields of a variant are compared in order too
+    assert!(matches!(
+        check(line_number!(37)),
+        Err(CsError::OrderDependent(_))
+    ));
+    assert!(check(line_number!(43)).is_ok());
+    // the items are still recognized by the other analyzers, which
+    // don't know the Rust rules
+    let range = list.range_around_line_number(line_number!(4)).unwrap();
+    assert!(list.check_order_independent(range, Analyzer::Java).is_ok());
+}
@@ -0,0 +1,70 @@
+use codesort::*;
+
+#[test]
+fn test_partial_selection_strict() {
+    static INPUT: &str = r#"
+            Self::Zig => Analyzer::Rust,
+            Self::C => Analyzer::Rust,
+            Self::Javascript => Analyzer::Javascript,
+        }
+    }
+    "#;
+    assert!(matches!(
+        LocList::read_str(INPUT, Language::Rust),
+        Err(CsError::UnexpectedClosingBrace('}')),
+    ));
+}
+
+#[test]
+fn test_partial_selection_tail_of_match() {
+    static INPUT: &str = r#"
+            Self::Zig => Analyzer::Rust,
+            Self::C => Analyzer::Rust, // should be OK
+            Self::Javascript => {
+                Analyzer::Javascript
+            }
+        }
+    }
+    "#;
+    static OUTPUT: &str = r#"
+            Self::C => Analyzer::Rust, // should be OK
+            Self::Javascript => {
+                Analyzer::Javascript
+            }
+            Self::Zig => Analyzer::Rust,
+        }
+    }
+    "#;
+    let list = LocList::read_str_lenient(INPUT, Language::Rust).unwrap();
+    assert_eq!(list.locs[1].start_depth, 2);
+    assert_eq!(list.locs.last().unwrap().end_depth, 0);
+    let focused = list.focus_floor().unwrap();
+    assert_eq!(focused.focus.len(), 5);
+    let sorted_list = focused.sort();
+    assert_eq!(sorted_list.to_string(), OUTPUT);
+}
+
+#[test]
+fn test_partial_selection_mismatched_brace() {
+    static INPUT: &str = r#"
+            Foo(a, b],
+        }
+    "#;
+    assert!(LocList::read_str_lenient(INPUT, Language::Rust).is_err());
+}
+
+#[test]
+fn test_balanced_selection_lenient() {
+    static INPUT: &str = r#"
+    enum Analyzer {
+        Rust,
+        Java,
+    }
+    "#;
+    let strict = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let lenient = LocList::read_str_lenient(INPUT, Language::Rust).unwrap();
+    for (a, b) in strict.locs.iter().zip(lenient.locs.iter()) {
+        assert_eq!(a.start_depth, b.start_depth);
+        assert_eq!(a.end_depth, b.end_depth);
+    }
+}
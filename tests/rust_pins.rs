@@ -0,0 +1,98 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+fn describe(c: Choice) -> &'static str {
+    match c {
+        Choice::Gamma => "gamma",
+        _ => "other",
+        Choice::Beta => "beta",
+        Choice::Alpha => "alpha",
+    }
+}
+
+pub enum Choice {
+    Gamma,
+    Unknown, // codesort: pin-last
+    Beta,
+    // codesort: pin-first
+    None,
+    Alpha,
+}
+
+fn config() -> Config {
+    Config {
+        width: 3,
+        height: 4,
+        ..Default::default()
+    }
+}
+"#;
+
+static OUTPUT: &str = r#"
+fn describe(c: Choice) -> &'static str {
+    match c {
+        Choice::Alpha => "alpha",
+        Choice::Beta => "beta",
+        Choice::Gamma => "gamma",
+        _ => "other",
+    }
+}
+
+pub enum Choice {
+    // codesort: pin-first
+    None,
+    Alpha,
+    Beta,
+    Gamma,
+    Unknown, // codesort: pin-last
+}
+
+fn config() -> Config {
+    Config {
+        height: 4,
+        width: 3,
+        ..Default::default()
+    }
+}
+"#;
+
+#[test]
+fn test_pins() {
+    let mut list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    // the pins alone, without the order constraints of the analyzer, which
+    // would keep the arms following the catch-all after it
+    list.lang = None;
+    list.sort_around_line_number(line_number!(4)).unwrap();
+    list.sort_around_line_number(line_number!(13)).unwrap();
+    list.sort_around_line_number(line_number!(23)).unwrap();
+    assert_eq!(list.to_string(), OUTPUT);
+}
+
+#[test]
+fn test_range_and_guarded_arms_are_not_pinned() {
+    static INPUT: &str = r#"
+fn kind(n: u32, ready: bool) -> &'static str {
+    match n {
+        n if ready => "ready",
+        ..=9 => "digit",
+        10 => "ten",
+    }
+}
+"#;
+    static OUTPUT: &str = r#"
+fn kind(n: u32, ready: bool) -> &'static str {
+    match n {
+        ..=9 => "digit",
+        10 => "ten",
+        n if ready => "ready",
+    }
+}
+"#;
+    let mut list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    assert_eq!(list.locs[3].pin, Pin::Unpinned);
+    assert_eq!(list.locs[4].pin, Pin::Unpinned);
+    // the guarded arm overlaps the other ones, so only the pins are tested
+    list.lang = None;
+    list.sort_around_line_number(line_number!(4)).unwrap();
+    assert_eq!(list.to_string(), OUTPUT);
+}
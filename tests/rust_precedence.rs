@@ -0,0 +1,77 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+pub enum Level {
+    Error,
+    Off,
+    Warn,
+    /// very verbose
+    Trace,
+    Info,
+    All,
+    Debug,
+}
+
+fn color(level: Level) -> Color {
+    match level {
+        Level::Warn => Color::Yellow,
+        Level::Error => Color::Red,
+        Level::Debug | Level::Trace => Color::Grey,
+        _ => Color::White,
+    }
+}
+"#;
+
+static OUTPUT: &str = r#"
+pub enum Level {
+    /// very verbose
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    All,
+    Off,
+}
+
+fn color(level: Level) -> Color {
+    match level {
+        Level::Debug | Level::Trace => Color::Grey,
+        Level::Warn => Color::Yellow,
+        Level::Error => Color::Red,
+        _ => Color::White,
+    }
+}
+"#;
+
+#[test]
+fn test_block_identifier() {
+    let identifier = |code: &str| {
+        LocList::read_str(code, Language::Rust)
+            .unwrap()
+            .identifier()
+    };
+    assert_eq!(identifier("Level::Warn => 3,"), Some("Warn".to_string()));
+    assert_eq!(
+        identifier("pub(crate) name: String,"),
+        Some("name".to_string())
+    );
+    assert_eq!(identifier("\"GET\" => get(),"), Some("GET".to_string()));
+    assert_eq!(identifier("// only a comment"), None);
+}
+
+#[test]
+fn test_sort_with_precedence() {
+    let order: Vec<String> = ["Trace", "Debug", "Info", "Warn", "Error"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    let mut list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    for line in [3, 16] {
+        let focused = list
+            .focus_around_line_number(LineNumber::new(line).unwrap())
+            .unwrap();
+        list = focused.sort_by(BlockComparator::with_precedence(order.clone()));
+    }
+    assert_eq!(list.to_string(), OUTPUT);
+}
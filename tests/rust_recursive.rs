@@ -0,0 +1,68 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+let config = Config {
+    server: Server {
+        port: 80,
+        host: "x",
+        tls: Tls {
+            key: "k",
+            cert: "c",
+        },
+    },
+    client: Client {
+        timeout: 3,
+        retries: 2,
+    },
+};
+"#;
+
+static OUTPUT_DEPTH_1: &str = r#"
+let config = Config {
+    client: Client {
+        retries: 2,
+        timeout: 3,
+    },
+    server: Server {
+        host: "x",
+        port: 80,
+        tls: Tls {
+            key: "k",
+            cert: "c",
+        },
+    },
+};
+"#;
+
+static OUTPUT: &str = r#"
+let config = Config {
+    client: Client {
+        retries: 2,
+        timeout: 3,
+    },
+    server: Server {
+        host: "x",
+        port: 80,
+        tls: Tls {
+            cert: "c",
+            key: "k",
+        },
+    },
+};
+"#;
+
+#[test]
+fn test_recursive_sort() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let range = list.range_around_line_number(line_number!(3)).unwrap();
+    assert_eq!(list.nested_ranges(range, Some(1)).len(), 2);
+    assert_eq!(list.nested_ranges(range, None).len(), 3);
+
+    let mut sorted = list.clone();
+    sorted.sort_range_recursive(range, Some(1)).unwrap();
+    assert_eq!(sorted.to_string(), OUTPUT_DEPTH_1);
+
+    let mut sorted = list.clone();
+    sorted.sort_range_recursive(range, None).unwrap();
+    assert_eq!(sorted.to_string(), OUTPUT);
+}
@@ -0,0 +1,89 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+#[derive(Debug)]
+pub enum Shape {
+    Square,
+    Circle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Low,
+    High,
+}
+
+#[repr(u8)]
+enum Code {
+    B = 2,
+    A = 1,
+}
+
+pub(crate) struct Point {
+    y: f64,
+    x: f64,
+}
+
+fn name(shape: Shape) -> impl Iterator<Item = char> {
+    let name = match shape {
+        Shape::Square => "square",
+        Shape::Circle => "circle",
+    };
+    name.chars()
+}
+"#;
+
+static OUTPUT: &str = r#"
+#[derive(Debug)]
+pub enum Shape {
+    Circle,
+    Square,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Low,
+    High,
+}
+
+#[repr(u8)]
+enum Code {
+    B = 2,
+    A = 1,
+}
+
+pub(crate) struct Point {
+    y: f64,
+    x: f64,
+}
+
+fn name(shape: Shape) -> impl Iterator<Item = char> {
+    let name = match shape {
+        Shape::Circle => "circle",
+        Shape::Square => "square",
+    };
+    name.chars()
+}
+"#;
+
+#[test]
+fn test_sort_all_enums_and_matches() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let selection = ItemSelection::new(vec![ItemKind::Enum, ItemKind::Match]);
+    let ranges = list.item_ranges(Analyzer::Rust, &selection);
+    assert_eq!(ranges.len(), 2);
+    let mut sorted = list.clone();
+    sorted.sort_ranges(&ranges).unwrap();
+    assert_eq!(sorted.to_string(), OUTPUT);
+}
+
+#[test]
+fn test_excluding_keywords() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let mut selection = ItemSelection::new(vec![ItemKind::Enum, ItemKind::Struct]);
+    assert_eq!(list.item_ranges(Analyzer::Rust, &selection).len(), 2);
+    selection.excluding_keywords = vec!["repr".to_string()];
+    let ranges = list.item_ranges(Analyzer::Rust, &selection);
+    assert_eq!(ranges.len(), 3);
+    assert_eq!(ranges[1].start, line_number!(10));
+}
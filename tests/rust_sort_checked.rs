@@ -0,0 +1,59 @@
+use codesort::*;
+
+#[test]
+fn test_sort_checked_ok() {
+    static INPUT: &str = r#"
+    match x {
+        C => 3,
+        B => {
+            2
+        }
+    }
+    "#;
+    static OUTPUT: &str = r#"
+    match x {
+        B => {
+            2
+        }
+        C => 3,
+    }
+    "#;
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_index(2).unwrap();
+    let sorted_list = focused.sort_checked(Language::Rust).unwrap();
+    assert_eq!(sorted_list.to_string(), OUTPUT);
+}
+
+#[test]
+fn test_sort_checked_range_cutting_a_block() {
+    static INPUT: &str = r#"
+    match x {
+        C => 3,
+        B => {
+            2
+        }
+    }
+    "#;
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let range = LineNumberRange {
+        start: LineNumber::new(3).unwrap(),
+        end: LineNumber::new(5).unwrap(),
+    };
+    let focused = list.focus(range).unwrap();
+    assert!(matches!(
+        focused.sort_checked(Language::Rust),
+        Err(CsError::SortRejected(_)),
+    ));
+}
+
+#[test]
+fn test_sort_checked_no_final_newline() {
+    static INPUT: &str = "B,\nA,";
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_all().unwrap();
+    assert_eq!(focused.clone().sort().to_string(), "A,B,\n");
+    assert!(matches!(
+        focused.sort_checked(Language::Rust),
+        Err(CsError::SortRejected(_)),
+    ));
+}
@@ -0,0 +1,69 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+fn colors() -> Vec<Color> {
+    // codesort: sort
+    let default = Color::Black;
+    let all = vec![
+        Color::White,
+        Color::Red,
+    ];
+    all
+}
+
+enum Shade { // codesort: sort
+    Light,
+    Dark,
+}
+
+// codesort: sort
+enum Hue {
+    Warm,
+    Cold,
+}
+"#;
+
+static OUTPUT: &str = r#"
+fn colors() -> Vec<Color> {
+    // codesort: sort
+    let default = Color::Black;
+    let all = vec![
+        Color::White,
+        Color::Red,
+    ];
+    all
+}
+
+enum Shade { // codesort: sort
+    Dark,
+    Light,
+}
+
+// codesort: sort
+enum Hue {
+    Cold,
+    Warm,
+}
+"#;
+
+#[test]
+fn test_marker_applies_only_to_the_next_line() {
+    let mut list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    // the marker above a statement not opening a list doesn't select
+    // the list opened further down
+    assert_eq!(
+        list.marked_ranges(),
+        vec![
+            LineNumberRange {
+                start: line_number!(13),
+                end: line_number!(14),
+            },
+            LineNumberRange {
+                start: line_number!(19),
+                end: line_number!(20),
+            },
+        ]
+    );
+    assert_eq!(list.sort_marked().unwrap(), 2);
+    assert_eq!(list.to_string(), OUTPUT);
+}
@@ -0,0 +1,86 @@
+use codesort::*;
+
+#[test]
+fn test_sort_report() {
+    static INPUT: &str = r#"
+    enum Choice {
+        Gamma,
+        Beta {
+            width: usize,
+        },
+        Delta,
+        Alpha,
+    }
+    "#;
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_number(line_number!(3)).unwrap();
+    let original = focused.clone();
+    let (sorted_list, moves) = focused.sort_with_moves();
+    let report = SortReport::new(&original, &sorted_list, moves);
+    assert_eq!(
+        report.ranges,
+        vec![LineNumberRange {
+            start: line_number!(3),
+            end: line_number!(8),
+        }]
+    );
+    assert!(report.warnings.is_empty());
+    let keys: Vec<&str> = report.blocks.iter().map(|b| b.sort_key.as_str()).collect();
+    assert_eq!(keys, vec!["Alpha,", "Beta{", "Delta,", "Gamma,"]);
+    assert_eq!(
+        report.blocks[1],
+        BlockMove {
+            old_range: LineNumberRange {
+                start: line_number!(4),
+                end: line_number!(6),
+            },
+            new_range: LineNumberRange {
+                start: line_number!(4),
+                end: line_number!(6),
+            },
+            sort_key: "Beta{".to_string(),
+        }
+    );
+    // Beta and Delta don't move, so there are only two small edits
+    assert_eq!(
+        report.edits,
+        vec![
+            TextEdit {
+                start: line_number!(3),
+                end: line_number!(3),
+                replacement: "        Alpha,\n".to_string(),
+            },
+            TextEdit {
+                start: line_number!(8),
+                end: line_number!(8),
+                replacement: "        Gamma,\n".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_sort_report_trailing_comment() {
+    static INPUT: &str = r#"
+    enum Choice {
+        Beta,
+        Alpha,
+        // Gamma, not ready
+    }
+    "#;
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    // the range around a line ends at its last block, so the comment
+    // is only sorted with an explicit range
+    let focused = list
+        .focus(LineNumberRange {
+            start: line_number!(3),
+            end: line_number!(5),
+        })
+        .unwrap();
+    let original = focused.clone();
+    let (sorted_list, moves) = focused.sort_with_moves();
+    let report = SortReport::new(&original, &sorted_list, moves);
+    // the comment is a block of its own, staying at the end, without warning
+    assert_eq!(report.blocks.len(), 3);
+    assert!(report.warnings.is_empty());
+}
@@ -0,0 +1,89 @@
+use codesort::*;
+
+#[test]
+fn test_sort_verified_ok() {
+    static INPUT: &str = r#"
+    pub enum ContentSearchResult {
+        /// the needle hasn't been found
+        NotFound, // no match
+        /// the needle has been found at the given pos
+        Found {
+            pos: usize,
+        },
+    }
+    "#;
+    static OUTPUT: &str = r#"
+    pub enum ContentSearchResult {
+        /// the needle has been found at the given pos
+        Found {
+            pos: usize,
+        },
+        /// the needle hasn't been found
+        NotFound, // no match
+    }
+    "#;
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_index(3).unwrap();
+    let sorted_list = focused.sort_verified().unwrap();
+    assert_eq!(sorted_list.to_string(), OUTPUT);
+}
+
+#[test]
+fn test_sort_verified_trailing_comment() {
+    static INPUT: &str = r#"
+    enum Choice {
+        Beta,
+        Alpha,
+        // end of choices
+    }
+    "#;
+    static OUTPUT: &str = r#"
+    enum Choice {
+        Alpha,
+        Beta,
+        // end of choices
+    }
+    "#;
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let range = LineNumberRange {
+        start: LineNumber::new(3).unwrap(),
+        end: LineNumber::new(5).unwrap(),
+    };
+    let focused = list.focus(range).unwrap();
+    // the trailing comment stays at the end, instead of moving to the top
+    // and sticking to the first variant
+    let sorted_list = focused.sort_verified().unwrap();
+    assert_eq!(sorted_list.to_string(), OUTPUT);
+}
+
+#[test]
+fn test_sort_verified_comment_reassociated() {
+    static INPUT: &str = r#"
+    enum Choice {
+        /// the first one
+        Beta,
+        /// the second one
+        Alpha,
+    }
+    "#;
+    // the same lines, but each comment now documents the other variant
+    static REASSOCIATED: &str = r#"
+    enum Choice {
+        /// the second one
+        Beta,
+        /// the first one
+        Alpha,
+    }
+    "#;
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let focused = list.focus_around_line_index(3).unwrap();
+    let reassociated = LocList::read_str(REASSOCIATED, Language::Rust).unwrap();
+    // the line and depth checks don't see it, the tokens of the blocks do
+    assert!(focused.check_sorted(&reassociated, Language::Rust).is_ok());
+    assert!(matches!(
+        focused.verify_sorted(&reassociated),
+        Err(CsError::SortRejected(_)),
+    ));
+    // while the real sort keeps each comment with its variant
+    assert!(focused.sort_verified().is_ok());
+}
@@ -0,0 +1,109 @@
+use codesort::*;
+
+static BEFORE_MULTI_LINE: &str = r#"
+struct Settings {
+    zoom: f32,
+    width: u16,
+
+    /// the displayed title
+    title: String,
+    height: u16,
+
+    /// whether to show the borders
+    borders: bool,
+}
+"#;
+
+static BEFORE_MULTI_LINE_SORTED: &str = r#"
+struct Settings {
+    /// whether to show the borders
+    borders: bool,
+
+    height: u16,
+
+    /// the displayed title
+    title: String,
+    width: u16,
+    zoom: f32,
+}
+"#;
+
+static INCONSISTENT: &str = r#"
+struct Settings {
+    zoom: f32,
+
+    width: u16,
+    /// the displayed title
+    title: String,
+
+
+    height: u16,
+
+    /// whether to show the borders
+    borders: bool,
+}
+"#;
+
+static NORMALIZED: &str = r#"
+struct Settings {
+    /// whether to show the borders
+    borders: bool,
+
+    height: u16,
+
+    /// the displayed title
+    title: String,
+
+    width: u16,
+
+    zoom: f32,
+}
+"#;
+
+#[test]
+fn test_recognize_spacings() {
+    let spacing = |input: &str| {
+        let list = LocList::read_str(input, Language::Rust).unwrap();
+        let focused = list.focus_around_line_number(line_number!(3)).unwrap();
+        Spacing::recognize(&focused.focus.into_blocks())
+    };
+    assert_eq!(spacing(BEFORE_MULTI_LINE), Spacing::BeforeMultiLine);
+    assert_eq!(spacing(INCONSISTENT), Spacing::Other);
+    assert_eq!(spacing("enum A {\n    B,\n    C,\n}\n"), Spacing::Compact);
+}
+
+#[test]
+fn test_keep_spacing_before_multi_line() {
+    let list = LocList::read_str(BEFORE_MULTI_LINE, Language::Rust).unwrap();
+    let focused = list.focus_around_line_number(line_number!(3)).unwrap();
+    let sorted = focused.sort();
+    assert_eq!(sorted.to_string(), BEFORE_MULTI_LINE_SORTED);
+}
+
+#[test]
+fn test_normalize_spacing() {
+    let list = LocList::read_str(INCONSISTENT, Language::Rust).unwrap();
+    let line_count = list.len();
+    let focused = list.focus_around_line_number(line_number!(3)).unwrap();
+    let options = SortOptions {
+        spacing: SpacingMode::Normalize,
+        ..Default::default()
+    };
+    let (sorted, moves) = focused.clone().sort_with(&options);
+    assert_eq!(sorted.to_string(), NORMALIZED);
+    focused
+        .check_sorted_with(&sorted, Language::Rust, &options)
+        .unwrap();
+    // a removed blank line is rejected, unless the spacing is normalized
+    let mut squeezed = sorted.clone();
+    let blank = squeezed.locs.iter().position(|loc| loc.is_blank()).unwrap();
+    squeezed.locs.remove(blank);
+    assert!(focused.check_sorted(&squeezed, Language::Rust).is_err());
+    focused
+        .check_sorted_with(&squeezed, Language::Rust, &options)
+        .unwrap();
+
+    // the cursor, on the `title` field, follows it
+    let mapping = LineMapping::new(line_count, &moves);
+    assert_eq!(mapping.new_line_number(line_number!(7)), line_number!(9));
+}
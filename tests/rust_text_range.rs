@@ -0,0 +1,52 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+pub enum Choice {
+    Gamma,
+    Beta {
+        width: usize,
+        height: usize,
+    },
+    Delta,
+    Alpha,
+}
+"#;
+
+#[test]
+fn test_snap_selection_to_blocks() {
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    // from the middle of Gamma to the middle of Beta
+    let range: TextRange = "3:7-5:3".parse().unwrap();
+    assert_eq!(list.snap_range(range).unwrap().to_string(), "3:7");
+    // a selection ending at the start of line 9 doesn't include Alpha
+    let range: TextRange = "4:1-9:1".parse().unwrap();
+    assert_eq!(list.snap_range(range).unwrap().to_string(), "4:8");
+    // a selection going past the end of the enum stays in the enum
+    let range: TextRange = "8:5-9:10".parse().unwrap();
+    assert_eq!(list.snap_range(range).unwrap().to_string(), "8:9");
+}
+
+#[test]
+fn test_sort_in_line() {
+    static INPUT: &str = r#"
+use std::{io, fs, path::Path};
+fn check(width: usize, height: usize) {}
+"#;
+    let list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    let range: TextRange = "2:2".parse().unwrap();
+    let (sorted, _) = list.sort_in_line(range, Language::Rust).unwrap();
+    assert_eq!(
+        sorted.to_string(),
+        r#"
+use std::{fs, io, path::Path};
+fn check(width: usize, height: usize) {}
+"#
+    );
+    let range: TextRange = "3:10-3:36".parse().unwrap();
+    let (sorted, _) = sorted.sort_in_line(range, Language::Rust).unwrap();
+    assert!(
+        sorted
+            .to_string()
+            .contains("fn check(height: usize, width: usize) {}")
+    );
+}
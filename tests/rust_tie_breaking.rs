@@ -0,0 +1,66 @@
+use codesort::*;
+
+static INPUT: &str = r#"
+fn open(platform: Platform, path: &Path) -> io::Result<File> {
+    match platform {
+        Platform::Web => web::open(path),
+        #[cfg(windows)]
+        Platform::Native => native::open(path),
+        #[cfg(unix)]
+        Platform::Native => native::open(path),
+        Platform::Embedded => embedded::open(path), // slow
+        Platform::Embedded => embedded::open(path), // fast
+    }
+}
+"#;
+
+static OUTPUT: &str = r#"
+fn open(platform: Platform, path: &Path) -> io::Result<File> {
+    match platform {
+        Platform::Embedded => embedded::open(path), // fast
+        Platform::Embedded => embedded::open(path), // slow
+        #[cfg(unix)]
+        Platform::Native => native::open(path),
+        #[cfg(windows)]
+        Platform::Native => native::open(path),
+        Platform::Web => web::open(path),
+    }
+}
+"#;
+
+static OUTPUT_BY_KEY_ONLY: &str = r#"
+fn open(platform: Platform, path: &Path) -> io::Result<File> {
+    match platform {
+        Platform::Embedded => embedded::open(path), // slow
+        Platform::Embedded => embedded::open(path), // fast
+        #[cfg(windows)]
+        Platform::Native => native::open(path),
+        #[cfg(unix)]
+        Platform::Native => native::open(path),
+        Platform::Web => web::open(path),
+    }
+}
+"#;
+
+// The arms with equal patterns overlap, so the language is forgotten
+// for the analyzer not to keep their order: only the comparator is tested
+
+#[test]
+fn test_tie_breaking() {
+    let mut list = LocList::read_str(INPUT, Language::Rust).unwrap();
+    list.lang = None;
+    let focused = list.focus_around_line_number(line_number!(4)).unwrap();
+    assert_eq!(focused.clone().sort().to_string(), OUTPUT);
+
+    // only comparing the sort keys, equal blocks keep their order
+    let comparator = BlockComparator::new(vec![Criterion::SortKey]);
+    assert_eq!(focused.sort_by(comparator).to_string(), OUTPUT_BY_KEY_ONLY);
+}
+
+#[test]
+fn test_tie_breaking_is_deterministic() {
+    let mut list = LocList::read_str(OUTPUT_BY_KEY_ONLY, Language::Rust).unwrap();
+    list.lang = None;
+    let focused = list.focus_around_line_number(line_number!(4)).unwrap();
+    assert_eq!(focused.sort().to_string(), OUTPUT);
+}